@@ -0,0 +1,50 @@
+use std::num::NonZeroUsize;
+use bevy_neat::{Config, FeedForwardGenome, InitialTopology, traits::Genome};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use rand::Rng;
+
+// Samples per batch, matching a typical fitness evaluation over a data set.
+const SAMPLES: usize = 512;
+
+/// Grows a fully connected genome with structural mutations until it has a few hundred connections.
+fn genome(config: &Config<FeedForwardGenome>) -> FeedForwardGenome {
+    let mut genome = FeedForwardGenome::minimal(config);
+
+    for _ in 0..40 {
+        genome.add_node_mut(config);
+    }
+
+    for _ in 0..300 {
+        genome.add_conn_mut(config);
+    }
+
+    genome
+}
+
+fn batch_vs_per_sample(c: &mut Criterion) {
+    let config = Config::new(NonZeroUsize::new(8).unwrap(), NonZeroUsize::new(4).unwrap(), NonZeroUsize::new(10).unwrap(), 3.0, 1.0, 1.0, 0.4)
+        .with_initial_topology(InitialTopology::FullyConnected);
+
+    let mut network = genome(&config).compile(&config);
+    let mut rng = rand::thread_rng();
+
+    let inputs = (0..SAMPLES * network.input_len()).map(|_| rng.gen_range(-1.0..1.0)).collect::<Vec<f32>>();
+    let mut outputs = vec![0.0; SAMPLES * network.output_len()];
+
+    let mut group = c.benchmark_group("activate");
+    let edges = network.edge_count();
+
+    group.bench_function(BenchmarkId::new("per_sample", edges), |b| b.iter(|| {
+        let (input_len, output_len) = (network.input_len(), network.output_len());
+
+        for (row, output) in inputs.chunks_exact(input_len).zip(outputs.chunks_exact_mut(output_len)) {
+            network.activate_into(row, output);
+        }
+    }));
+
+    group.bench_function(BenchmarkId::new("batch", edges), |b| b.iter(|| network.activate_batch_into(&inputs, &mut outputs)));
+    group.finish();
+}
+
+criterion_group!(benches, batch_vs_per_sample);
+criterion_main!(benches);
\ No newline at end of file
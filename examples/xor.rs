@@ -18,7 +18,7 @@ fn main() {
 
 fn setup(mut population: ResMut<Population<FeedForwardGenome>>) {
     population.run(|genome, config| {
-        XOR_INPUTS.into_iter().zip(XOR_OUTPUTS.into_iter()).map(|(xi, xo)| {
+        XOR_INPUTS.into_iter().zip(XOR_OUTPUTS).map(|(xi, xo)| {
             let output = genome.activate([xi.0, xi.1].into(), config);
             -(output.first().cloned().unwrap() - xo).powi(2)
         }).sum::<f32>().add(4.0)
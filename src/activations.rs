@@ -2,7 +2,7 @@ use core::f32;
 use std::ops::{Add, Div};
 use crate::traits;
 
-#[derive(Default)]
+#[derive(Clone, Copy, Debug, Default)]
 pub struct Sigmoid;
 
 impl traits::Activation for Sigmoid {
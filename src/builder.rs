@@ -0,0 +1,130 @@
+use std::{collections::{BTreeMap, BTreeSet}, error, fmt};
+use crate::{Config, FeedForwardGenome, traits::Config as _};
+
+/// Declares the nodes and connections of a [`FeedForwardGenome`] by hand, such as a known solution used as a fixture
+/// or as a seed.
+///
+/// Input and output ids are fixed by the config and returned by [`GenomeBuilder::input`] and
+/// [`GenomeBuilder::output`]. Hidden nodes are allocated from the config so that their ids never clash with the ones
+/// handed out by mutations, and every connection draws its innovation number from the config history.
+pub struct GenomeBuilder<'a> {
+    config: &'a Config<FeedForwardGenome>,
+    hidden: Vec<u32>,
+    conns: Vec<(u32, u32, f32, bool)>,
+}
+
+impl<'a> GenomeBuilder<'a> {
+    pub fn new(config: &'a Config<FeedForwardGenome>) -> Self {
+        Self { config, hidden: Vec::new(), conns: Vec::new() }
+    }
+
+    pub fn input(&self, index: usize) -> u32 {
+        assert!(index < self.config.input_len());
+        index as u32
+    }
+
+    pub fn output(&self, index: usize) -> u32 {
+        assert!(index < self.config.output_len());
+        (self.config.input_len() + index) as u32
+    }
+
+    /// Adds a hidden node and returns its id.
+    pub fn hidden(&mut self) -> u32 {
+        let id = self.config.node_id();
+        self.hidden.push(id);
+        id
+    }
+
+    pub fn conn(&mut self, in_node: u32, out_node: u32, weight: f32) -> &mut Self {
+        self.conns.push((in_node, out_node, weight, true));
+        self
+    }
+
+    pub fn disabled_conn(&mut self, in_node: u32, out_node: u32, weight: f32) -> &mut Self {
+        self.conns.push((in_node, out_node, weight, false));
+        self
+    }
+
+    /// Checks the declared network and builds the genome, failing on the first problem found. Weights are kept as
+    /// given, even outside the config's weight bounds.
+    pub fn build(&self) -> Result<FeedForwardGenome, BuildError> {
+        let (input_len, output_len) = (self.config.input_len() as u32, self.config.output_len() as u32);
+        let hidden = BTreeSet::from_iter(self.hidden.iter().copied());
+        let mut pairs = BTreeSet::new();
+
+        for &(in_node, out_node, ..) in self.conns.iter() {
+            if let Some(&node) = [in_node, out_node].iter().find(|&&node| node >= input_len + output_len && !hidden.contains(&node)) {
+                return Err(BuildError::UnknownNode(node));
+            }
+
+            if out_node < input_len {
+                return Err(BuildError::ConnIntoInput { in_node, out_node });
+            }
+
+            if (input_len..input_len + output_len).contains(&in_node) {
+                return Err(BuildError::ConnFromOutput { in_node, out_node });
+            }
+
+            if !pairs.insert((in_node, out_node)) {
+                return Err(BuildError::DuplicateConn { in_node, out_node });
+            }
+        }
+
+        // Disabled connections count towards cycles too, since they may be re-enabled later.
+        let mut in_degree = BTreeMap::from_iter(hidden.iter().map(|&node| (node, 0)));
+
+        for (_, out_node) in pairs.iter().filter(|(in_node, _)| hidden.contains(in_node)) {
+            in_degree.entry(*out_node).and_modify(|degree| *degree += 1);
+        }
+
+        let mut ready = in_degree.iter().filter(|(_, degree)| degree.eq(&&0)).map(|(&node, _)| node).collect::<Vec<_>>();
+        let mut visited = 0;
+
+        while let Some(node) = ready.pop() {
+            visited += 1;
+
+            for (_, out_node) in pairs.iter().filter(|(in_node, _)| in_node.eq(&node)) {
+                if let Some(degree) = in_degree.get_mut(out_node) {
+                    *degree -= 1;
+
+                    if *degree == 0 {
+                        ready.push(*out_node);
+                    }
+                }
+            }
+        }
+
+        if visited < hidden.len() {
+            return Err(BuildError::Cycle);
+        }
+
+        Ok(FeedForwardGenome::from_genes(self.config, &self.hidden, &self.conns))
+    }
+}
+
+/// The first problem [`GenomeBuilder::build`] finds in a declared network.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuildError {
+    /// A connection refers to a node that is neither an input, an output, nor a hidden node of the builder.
+    UnknownNode(u32),
+    ConnIntoInput { in_node: u32, out_node: u32 },
+    ConnFromOutput { in_node: u32, out_node: u32 },
+    /// The same pair of nodes is connected more than once.
+    DuplicateConn { in_node: u32, out_node: u32 },
+    /// The connections, disabled ones included, form a cycle through the hidden nodes.
+    Cycle,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownNode(node) => write!(f, "node {node} is not declared"),
+            Self::ConnIntoInput { in_node, out_node } => write!(f, "connection {in_node} -> {out_node} leads into an input node"),
+            Self::ConnFromOutput { in_node, out_node } => write!(f, "connection {in_node} -> {out_node} leads out of an output node"),
+            Self::DuplicateConn { in_node, out_node } => write!(f, "connection {in_node} -> {out_node} is declared more than once"),
+            Self::Cycle => write!(f, "the connections form a cycle"),
+        }
+    }
+}
+
+impl error::Error for BuildError { }
\ No newline at end of file
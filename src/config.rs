@@ -1,9 +1,13 @@
-use std::{collections::BTreeSet, num::NonZeroUsize, sync::atomic::{AtomicU32, Ordering}};
-use crate::{Sigmoid, traits::{self, ConnGene, Genome}};
+use std::{collections::BTreeMap, error, f32::consts::TAU, fmt, marker::PhantomData, num::NonZeroUsize, ops::RangeInclusive, sync::{Arc, Mutex, atomic::{AtomicU32, Ordering}}};
+use crate::{Classic, Sigmoid, Truncation, traits::{self, ConnGene, Genome, NodeGene}};
+use rand::Rng;
 
 #[derive(Clone)]
 pub struct Config<G> where G: Genome + Clone, G::ConnGene: Ord {
-    history: BTreeSet<G::ConnGene>,
+    history: Arc<Mutex<BTreeMap<(u32, u32), u32>>>,
+    innovs: Arc<Innov>,
+    node_ids: Arc<Innov>,
+    genome_ids: Arc<Innov>,
     input_len: usize,
     output_len: usize,
     pop_size: usize,
@@ -11,6 +15,23 @@ pub struct Config<G> where G: Genome + Clone, G::ConnGene: Ord {
     c1: f32,
     c2: f32,
     c3: f32,
+    max_stagnation: Option<usize>,
+    species_elitism: usize,
+    elitism: usize,
+    survival_threshold: f32,
+    min_species_size: usize,
+    species_target: Option<SpeciesTarget>,
+    selection: Arc<dyn traits::Selection<G>>,
+    speciation: Arc<dyn traits::Speciation<G>>,
+    interspecies_mating_rate: f64,
+    initial_topology: InitialTopology,
+    weight_init: WeightInit,
+    weight_bounds: RangeInclusive<f32>,
+    max_hidden_nodes: usize,
+    max_conns: usize,
+    node_penalty: f32,
+    conn_penalty: f32,
+    genome: PhantomData<G>,
 }
 
 impl<G> Config<G>
@@ -21,12 +42,168 @@ where
     pub fn new(input_len: NonZeroUsize, output_len: NonZeroUsize, pop_size: NonZeroUsize, comp_thresh: f32, c1: f32, c2: f32, c3: f32) -> Self {
         Self {
             history: Default::default(),
+            innovs: Default::default(),
+            node_ids: Arc::new(Innov::starting_at((input_len.get() + output_len.get()) as u32)),
+            genome_ids: Default::default(),
             input_len: input_len.into(),
             output_len: output_len.into(),
             pop_size: pop_size.into(),
-            comp_thresh, c1, c2, c3
+            comp_thresh, c1, c2, c3,
+            max_stagnation: None,
+            species_elitism: 0,
+            elitism: 0,
+            survival_threshold: 1.0,
+            min_species_size: 2,
+            species_target: None,
+            selection: Arc::new(Truncation::default()),
+            speciation: Arc::new(Classic::default()),
+            interspecies_mating_rate: 0.0,
+            initial_topology: InitialTopology::Unconnected,
+            weight_init: WeightInit::Uniform { min: -1.0, max: 1.0 },
+            weight_bounds: f32::NEG_INFINITY..=f32::INFINITY,
+            max_hidden_nodes: usize::MAX,
+            max_conns: usize::MAX,
+            node_penalty: 0.0,
+            conn_penalty: 0.0,
+            genome: PhantomData,
         }
     }
+
+    /// Copies the `elitism` fittest members of each species into the next generation unchanged.
+    pub fn with_elitism(mut self, elitism: usize) -> Self {
+        self.elitism = elitism;
+        self
+    }
+
+    /// Only lets the fittest `survival_threshold` fraction of each species, and at least one member, become parents.
+    pub fn with_survival_threshold(mut self, survival_threshold: f32) -> Self {
+        assert!(survival_threshold > 0.0 && survival_threshold <= 1.0);
+        self.survival_threshold = survival_threshold;
+        self
+    }
+
+    /// Guarantees every species at least `min_species_size` offspring, or an even share of `pop_size` when there are too
+    /// many species for that, before the rest is split by shared fitness. Without it, the least fit species would never
+    /// get any.
+    pub fn with_min_species_size(mut self, min_species_size: usize) -> Self {
+        self.min_species_size = min_species_size;
+        self
+    }
+
+    /// Treats `comp_thresh` as a starting point that the population adjusts every generation to keep the number of
+    /// species near a target. The target count and step must be positive, and `comp_thresh` must lie within the
+    /// target's bounds.
+    pub fn with_species_target(mut self, species_target: SpeciesTarget) -> Self {
+        assert!(species_target.count > 0, "species target count must be positive");
+        assert!(species_target.step > 0.0 && species_target.step.is_finite(), "species target step must be positive and finite");
+        assert!(species_target.min_thresh <= self.comp_thresh && self.comp_thresh <= species_target.max_thresh);
+        self.species_target = Some(species_target);
+        self
+    }
+
+    /// Sets how parents are picked within a species. Defaults to picking uniformly among the survivors.
+    pub fn with_selection(mut self, selection: impl traits::Selection<G> + 'static) -> Self {
+        self.selection = Arc::new(selection);
+        self
+    }
+
+    /// Sets how each generation is sorted into species. Defaults to [`Classic`] with random representatives.
+    pub fn with_speciation(mut self, speciation: impl traits::Speciation<G> + 'static) -> Self {
+        self.speciation = Arc::new(speciation);
+        self
+    }
+
+    /// Sets the chance that an offspring produced by crossover takes its second parent from another species.
+    pub fn with_interspecies_mating(mut self, interspecies_mating_rate: f64) -> Self {
+        assert!((0.0..=1.0).contains(&interspecies_mating_rate));
+        self.interspecies_mating_rate = interspecies_mating_rate;
+        self
+    }
+
+    /// Sets how the connections of minimal genomes are laid out. Hidden nodes created here get the same ids in every
+    /// genome so that their connections line up during crossover. Minimal genomes must fit the complexity limits.
+    pub fn with_initial_topology(mut self, initial_topology: InitialTopology) -> Self {
+        match initial_topology {
+            InitialTopology::Partial(fraction) => assert!((0.0..=1.0).contains(&fraction)),
+            // Reserve the ids of the hidden nodes, which directly follow the outputs, without handing them out.
+            InitialTopology::Hidden(hidden_len) if hidden_len > 0 => {
+                self.node_ids.skip_past((self.input_len + self.output_len + hidden_len - 1) as u32);
+            },
+            _ => (),
+        }
+
+        self.initial_topology = initial_topology;
+        self.check_initial_topology();
+        self
+    }
+
+    /// Sets the distribution new connection weights are drawn from. Defaults to uniform over `[-1, 1]`. A uniform range
+    /// may be a single value, which gives every new connection the same weight.
+    pub fn with_weight_init(mut self, weight_init: WeightInit) -> Self {
+        match weight_init {
+            WeightInit::Uniform { min, max } => {
+                assert!(min.is_finite() && max.is_finite(), "uniform weight init needs finite bounds");
+                assert!(min <= max, "uniform weight init needs min <= max");
+            },
+            WeightInit::Normal { mean, std_dev } => {
+                assert!(mean.is_finite() && std_dev.is_finite(), "normal weight init needs a finite mean and std_dev");
+                assert!(std_dev >= 0.0, "normal weight init needs a non-negative std_dev");
+            },
+        }
+
+        self.weight_init = weight_init;
+        self
+    }
+
+    /// Sets hard limits that every weight produced by initialization, mutation or crossover is clamped to.
+    pub fn with_weight_bounds(mut self, weight_bounds: RangeInclusive<f32>) -> Self {
+        assert!(weight_bounds.start() <= weight_bounds.end());
+        self.weight_bounds = weight_bounds;
+        self
+    }
+
+    /// Caps the number of hidden nodes and connection genes a structural mutation may grow a genome to. Minimal
+    /// genomes must fit the limits.
+    pub fn with_complexity_limits(mut self, max_hidden_nodes: usize, max_conns: usize) -> Self {
+        self.max_hidden_nodes = max_hidden_nodes;
+        self.max_conns = max_conns;
+        self.check_initial_topology();
+        self
+    }
+
+    /// Subtracts a penalty for every hidden node and every enabled connection from a genome's fitness before species
+    /// share it.
+    pub fn with_parsimony(mut self, node_penalty: f32, conn_penalty: f32) -> Self {
+        self.node_penalty = node_penalty;
+        self.conn_penalty = conn_penalty;
+        self
+    }
+
+    /// Removes species that have gone `max_stagnation` generations without improving their best fitness, except for
+    /// the `species_elitism` species with the highest best fitness.
+    pub fn with_stagnation(mut self, max_stagnation: NonZeroUsize, species_elitism: usize) -> Self {
+        self.max_stagnation = Some(max_stagnation.into());
+        self.species_elitism = species_elitism;
+        self
+    }
+
+    /// Checks that the largest minimal genome of the initial topology fits the complexity limits, so that the outcome
+    /// doesn't depend on which of the two is set first.
+    fn check_initial_topology(&self) {
+        let (hidden_len, conns) = match self.initial_topology {
+            InitialTopology::Unconnected => (0, 0),
+            InitialTopology::FullyConnected | InitialTopology::Partial(_) => (0, self.input_len * self.output_len),
+            InitialTopology::Hidden(hidden_len) => (hidden_len, (self.input_len + self.output_len) * hidden_len),
+        };
+
+        assert!(hidden_len <= self.max_hidden_nodes, "initial topology has more hidden nodes than the complexity limit");
+        assert!(conns <= self.max_conns, "initial topology has more connections than the complexity limit");
+    }
+
+    /// The innovation number recorded for a connection between two node ids, without recording a new one.
+    pub(crate) fn recorded_innov(&self, in_node: u32, out_node: u32) -> Option<u32> {
+        self.history.lock().unwrap().get(&(in_node, out_node)).copied()
+    }
 }
 
 impl<G> traits::Config<G> for Config<G>
@@ -38,19 +215,64 @@ where
     type Activation = Sigmoid;
 
     fn innov(&self, in_node: G::NodeGene, out_node: G::NodeGene) -> u32 {
-        self.history.iter()
-            .find(|c| (c.in_node(), c.out_node()).eq(&(in_node.clone(), out_node.clone())))
-            .and_then(|c| Some(c.innov()))
-            .unwrap_or(self.history.first().cloned().and_then(|c| Some(c.innov())).unwrap_or(0))
+        *self.history.lock().unwrap().entry((in_node.id(), out_node.id())).or_insert_with(|| self.innovs.next())
+    }
+
+    fn node_id(&self) -> u32 { self.node_ids.next() }
+
+    fn genome_id(&self) -> u32 { self.genome_ids.next() }
+
+    fn register(&self, genomes: &[G]) -> Result<(), Vec<InnovConflict>> {
+        let mut history = self.history.lock().unwrap();
+        let mut recorded = history.clone();
+        let mut pairs = BTreeMap::from_iter(history.iter().map(|(&pair, &innov)| (innov, pair)));
+        let mut conflicts = Vec::new();
+
+        for conn in genomes.iter().flat_map(|genome| genome.conn_genes()) {
+            let (pair, innov) = ((conn.in_node().id(), conn.out_node().id()), conn.innov());
+            let conflict = InnovConflict { in_node: pair.0, out_node: pair.1, innov };
+
+            let conflicting = recorded.get(&pair).is_some_and(|&recorded| recorded != innov)
+                || pairs.get(&innov).is_some_and(|&recorded| recorded != pair);
+
+            match conflicting {
+                true if !conflicts.contains(&conflict) => conflicts.push(conflict),
+                true => (),
+                false => {
+                    recorded.insert(pair, innov);
+                    pairs.insert(innov, pair);
+                },
+            }
+        }
+
+        if !conflicts.is_empty() {
+            return Err(conflicts);
+        }
+
+        for conn in genomes.iter().flat_map(|genome| genome.conn_genes()) {
+            self.innovs.skip_past(conn.innov());
+        }
+
+        for genome in genomes {
+            for node in genome.hidden_nodes() {
+                self.node_ids.skip_past(node.id());
+            }
+
+            self.genome_ids.skip_past(genome.lineage().id);
+        }
+
+        *history = recorded;
+
+        Ok(())
     }
 
     fn activation(&self) -> Self::Activation { Default::default() }
 
-    fn input_len(&self) -> usize { self.input_len.into() }
+    fn input_len(&self) -> usize { self.input_len }
 
-    fn output_len(&self) -> usize { self.output_len.into() }
+    fn output_len(&self) -> usize { self.output_len }
 
-    fn pop_size(&self) -> usize { self.pop_size.into() }
+    fn pop_size(&self) -> usize { self.pop_size }
 
     fn comp_thresh(&self) -> f32 { self.comp_thresh }
 
@@ -59,13 +281,111 @@ where
     fn c2(&self) -> f32 { self.c2 }
 
     fn c3(&self) -> f32 { self.c3 }
+
+    fn max_stagnation(&self) -> Option<usize> { self.max_stagnation }
+
+    fn species_elitism(&self) -> usize { self.species_elitism }
+
+    fn elitism(&self) -> usize { self.elitism }
+
+    fn survival_threshold(&self) -> f32 { self.survival_threshold }
+
+    fn min_species_size(&self) -> usize { self.min_species_size }
+
+    fn species_target(&self) -> Option<SpeciesTarget> { self.species_target }
+
+    fn selection(&self) -> &dyn traits::Selection<G> { self.selection.as_ref() }
+
+    fn speciation(&self) -> &dyn traits::Speciation<G> { self.speciation.as_ref() }
+
+    fn interspecies_mating_rate(&self) -> f64 { self.interspecies_mating_rate }
+
+    fn initial_topology(&self) -> InitialTopology { self.initial_topology }
+
+    fn weight_init(&self) -> WeightInit { self.weight_init }
+
+    fn weight_bounds(&self) -> RangeInclusive<f32> { self.weight_bounds.clone() }
+
+    fn max_hidden_nodes(&self) -> usize { self.max_hidden_nodes }
+
+    fn max_conns(&self) -> usize { self.max_conns }
+
+    fn node_penalty(&self) -> f32 { self.node_penalty }
+
+    fn conn_penalty(&self) -> f32 { self.conn_penalty }
 }
 
+/// The species count a population steers towards by raising its compatibility threshold by `step` when there are too
+/// many species and lowering it when there are too few, within `min_thresh..=max_thresh`.
+#[derive(Clone, Copy, Debug)]
+pub struct SpeciesTarget {
+    pub count: usize,
+    pub min_thresh: f32,
+    pub max_thresh: f32,
+    pub step: f32,
+}
+
+/// How the connections of a minimal genome are laid out.
+#[derive(Clone, Copy, Debug)]
+pub enum InitialTopology {
+    /// No connections at all.
+    Unconnected,
+    /// Every input connected to every output.
+    FullyConnected,
+    /// Each input connected to each output with the given probability.
+    Partial(f64),
+    /// The given number of hidden nodes, with every input connected to every hidden node and every hidden node
+    /// connected to every output.
+    Hidden(usize),
+}
+
+/// The distribution new connection weights are drawn from.
+#[derive(Clone, Copy, Debug)]
+pub enum WeightInit {
+    Uniform { min: f32, max: f32 },
+    Normal { mean: f32, std_dev: f32 },
+}
+
+impl WeightInit {
+    pub fn sample(&self, rng: &mut impl Rng) -> f32 {
+        match *self {
+            Self::Uniform { min, max } => rng.gen_range(min..=max),
+            // Box-Muller transform, with the first sample kept away from zero so that its logarithm is finite.
+            Self::Normal { mean, std_dev } => {
+                let (u1, u2) = (rng.gen_range(f32::EPSILON..1.0), rng.gen::<f32>());
+                mean + std_dev * (-2.0 * u1.ln()).sqrt() * (TAU * u2).cos()
+            },
+        }
+    }
+}
+
+/// A connection of a genome passed to `Config::register` whose innovation number disagrees with the config history or
+/// with another genome registered along with it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InnovConflict {
+    pub in_node: u32,
+    pub out_node: u32,
+    pub innov: u32,
+}
+
+impl fmt::Display for InnovConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "connection {} -> {} conflicts with the history at innovation #{}", self.in_node, self.out_node, self.innov)
+    }
+}
+
+impl error::Error for InnovConflict { }
+
 #[derive(Debug, Default)]
 pub struct Innov(AtomicU32);
 
 impl Innov {
+    pub fn starting_at(innov: u32) -> Self { Self(AtomicU32::new(innov)) }
+
     pub fn next(&self) -> u32 { self.0.fetch_add(1, Ordering::Relaxed) }
 
     pub fn current(&self) -> u32 { self.0.load(Ordering::Relaxed) }
+
+    /// Makes sure that `innov` is never handed out again.
+    pub fn skip_past(&self, innov: u32) { self.0.fetch_max(innov + 1, Ordering::Relaxed); }
 }
\ No newline at end of file
@@ -41,7 +41,7 @@ where
 
     fn out_node(&self) -> G::NodeGene { self.0.lock().unwrap().out_node.clone() }
 
-    fn weight(&self) -> f32 { self.0.lock().unwrap().weight.clone().into() }
+    fn weight(&self) -> f32 { self.0.lock().unwrap().weight }
 
     fn enabled(&self) -> bool { self.0.lock().unwrap().enabled }
 
@@ -52,9 +52,11 @@ impl<G> Eq for ConnGene<G> where G: traits::Genome, G::NodeGene: Debug { }
 
 impl<G> Ord for ConnGene<G> where G: traits::Genome, G::NodeGene: Debug {
     fn cmp(&self, other: &Self) -> Ordering {
-        Arc::ptr_eq(&self.0, &other.0)
-            .then_some(Ordering::Equal)
-            .unwrap_or(self.0.lock().unwrap().innov.cmp(&other.0.lock().unwrap().innov))
+        if Arc::ptr_eq(&self.0, &other.0) {
+            return Ordering::Equal;
+        }
+
+        self.0.lock().unwrap().innov.cmp(&other.0.lock().unwrap().innov)
     }
 }
 
@@ -66,6 +68,6 @@ impl<G> PartialEq for ConnGene<G> where G: traits::Genome, G::NodeGene: Debug {
 
 impl<G> PartialOrd for ConnGene<G> where G: traits::Genome, G::NodeGene: Debug {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.cmp(&other).into()
+        Some(self.cmp(other))
     }
 }
\ No newline at end of file
@@ -0,0 +1,24 @@
+/// What changed between two genomes of the same population, as returned by `FeedForwardGenome::diff`. Connections are
+/// given by innovation number and nodes by id, in ascending order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GenomeDiff {
+    pub added_conns: Vec<u32>,
+    pub removed_conns: Vec<u32>,
+    /// Matching connections whose enabled flag flipped, with the flag they ended up with.
+    pub enabled_changes: Vec<(u32, bool)>,
+    /// Matching connections whose weight moved by more than the threshold, with the weight before and after.
+    pub weight_changes: Vec<(u32, f32, f32)>,
+    pub added_nodes: Vec<u32>,
+    pub removed_nodes: Vec<u32>,
+}
+
+impl GenomeDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_conns.is_empty()
+            && self.removed_conns.is_empty()
+            && self.enabled_changes.is_empty()
+            && self.weight_changes.is_empty()
+            && self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,63 @@
+use std::fmt::Write;
+use crate::{Layout, traits::{self, ConnGene, NodeGene}};
+
+/// Renders a genome as a Graphviz DOT graph.
+///
+/// Inputs and outputs are placed in the first and last ranks, and hidden nodes in one rank per layer of their
+/// [`Layout`], so that deep networks keep their depth. Every connection is labelled with its weight and innovation
+/// number, and disabled connections are drawn dashed. Input and output nodes are labelled with the given names when
+/// there are enough of them, and with their index otherwise.
+pub fn to_dot<G: traits::Genome>(genome: &G, input_names: Option<&[&str]>, output_names: Option<&[&str]>) -> String {
+    let mut dot = String::new();
+
+    writeln!(dot, "digraph genome {{").unwrap();
+    writeln!(dot, "    rankdir=LR;").unwrap();
+
+    writeln!(dot, "    {{").unwrap();
+    writeln!(dot, "        rank=source;").unwrap();
+
+    for (i, node) in genome.input_nodes().enumerate() {
+        let name = input_names.and_then(|names| names.get(i)).map_or(format!("in {i}"), |name| name.to_string());
+        writeln!(dot, "        n{} [label={:?}, shape=box];", node.id(), name).unwrap();
+    }
+
+    writeln!(dot, "    }}").unwrap();
+
+    let layout = Layout::new(genome);
+    let layers = layout.layers();
+
+    for layer in layers[1..layers.len() - 1].iter() {
+        writeln!(dot, "    {{").unwrap();
+        writeln!(dot, "        rank=same;").unwrap();
+
+        for node in layer {
+            writeln!(dot, "        n{node} [label=\"{node}\", shape=circle];").unwrap();
+        }
+
+        writeln!(dot, "    }}").unwrap();
+    }
+
+    writeln!(dot, "    {{").unwrap();
+    writeln!(dot, "        rank=sink;").unwrap();
+
+    for (i, node) in genome.output_nodes().enumerate() {
+        let name = output_names.and_then(|names| names.get(i)).map_or(format!("out {i}"), |name| name.to_string());
+        writeln!(dot, "        n{} [label={:?}, shape=doublecircle];", node.id(), name).unwrap();
+    }
+
+    writeln!(dot, "    }}").unwrap();
+
+    for conn in genome.conn_genes() {
+        write!(dot, "    n{} -> n{} [label=\"{:.3} (#{})\"", conn.in_node().id(), conn.out_node().id(), conn.weight(), conn.innov()).unwrap();
+
+        if !conn.enabled() {
+            write!(dot, ", style=dashed").unwrap();
+        }
+
+        writeln!(dot, "];").unwrap();
+    }
+
+    writeln!(dot, "}}").unwrap();
+
+    dot
+}
@@ -0,0 +1,58 @@
+use std::iter;
+use crate::traits::{self, ConnGene, NodeGene};
+
+// Marks the boundary between the node lists and the connection list so that genes can't shift between them.
+const SEPARATOR: u64 = u64::MAX;
+
+// FNV-1a parameters. The hash is spelled out rather than taken from `std` so that it stays the same across Rust
+// versions and platforms.
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Compares two genomes by their genes: node ids by kind, and the innovation number, endpoints, weight and enabled
+/// flag of every connection. Fitness is ignored.
+///
+/// With a tolerance, weights are not compared by their distance. Each is rounded to the nearest multiple of the
+/// tolerance, and the rounded values must match, which keeps the comparison consistent with [`content_hash`]. So with a
+/// tolerance of `0.1`, `0.051` and `0.149` compare equal while `0.0499` and `0.0501` don't. Without a tolerance,
+/// weights must be bitwise identical. The tolerance must be positive and finite.
+pub fn structural_eq<G: traits::Genome>(lhs: &G, rhs: &G, tolerance: Option<f32>) -> bool {
+    check_tolerance(tolerance);
+    fingerprint(lhs, tolerance).eq(fingerprint(rhs, tolerance))
+}
+
+/// A hash of the same genes [`structural_eq`] compares, stable across runs and platforms. Genomes that are
+/// structurally equal under a tolerance hash equally under the same tolerance.
+pub fn content_hash<G: traits::Genome>(genome: &G, tolerance: Option<f32>) -> u64 {
+    check_tolerance(tolerance);
+
+    fingerprint(genome, tolerance)
+        .flat_map(u64::to_le_bytes)
+        .fold(FNV_OFFSET, |hash, byte| (hash ^ byte as u64).wrapping_mul(FNV_PRIME))
+}
+
+fn fingerprint<G: traits::Genome>(genome: &G, tolerance: Option<f32>) -> impl Iterator<Item = u64> + '_ {
+    let id = |node: &G::NodeGene| node.id() as u64;
+
+    genome.input_nodes().map(id).chain(iter::once(SEPARATOR))
+        .chain(genome.hidden_nodes().map(id)).chain(iter::once(SEPARATOR))
+        .chain(genome.output_nodes().map(id)).chain(iter::once(SEPARATOR))
+        .chain(genome.conn_genes().flat_map(move |conn| [
+            conn.innov() as u64,
+            conn.in_node().id() as u64,
+            conn.out_node().id() as u64,
+            weight_key(conn.weight(), tolerance),
+            conn.enabled() as u64,
+        ]))
+}
+
+fn check_tolerance(tolerance: Option<f32>) {
+    assert!(tolerance.is_none_or(|tolerance| tolerance > 0.0 && tolerance.is_finite()), "weight tolerance must be positive and finite");
+}
+
+fn weight_key(weight: f32, tolerance: Option<f32>) -> u64 {
+    match tolerance {
+        Some(tolerance) => ((weight / tolerance).round() as i64) as u64,
+        None => weight.to_bits() as u64,
+    }
+}
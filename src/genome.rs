@@ -1,38 +1,319 @@
-use std::{cmp, collections::{BTreeMap, BTreeSet}, fmt::Debug, iter, ops::{AddAssign, Sub}, sync::Arc};
-use crate::{config, conn, node, traits::{self, Activation, Config, ConnGene}};
+use std::{cmp, collections::{BTreeMap, BTreeSet, VecDeque}, fmt::Debug, hash::{Hash, Hasher}, sync::Arc};
+use crate::{config, conn, fingerprint, node, GenomeDiff, InitialTopology, Lineage, Network, traits::{self, Activation, Config, ConnGene, NodeGene}};
 use bevy::ecs::component::Component;
 use rand::{seq::{IteratorRandom, SliceRandom}, Rng};
 
 
-#[derive(Clone, Component, Debug, PartialEq)]
+#[derive(Component, Debug)]
 pub struct FeedForwardGenome {
     conn_genes: BTreeSet<conn::ConnGene<Self>>,
     input_nodes: Arc<[node::NodeGene<Self>]>,
     hidden_nodes: BTreeSet<node::NodeGene<Self>>,
     output_nodes: Arc<[node::NodeGene<Self>]>,
     fitness: Option<f32>,
+    objectives: Vec<f32>,
+    lineage: Lineage,
 }
 
 impl FeedForwardGenome {
-    fn matching_genes<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = (&conn::ConnGene<Self>, &conn::ConnGene<Self>)> + 'a {
+    fn matching_genes<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = (&'a conn::ConnGene<Self>, &'a conn::ConnGene<Self>)> + 'a {
         self.conn_genes.intersection(&other.conn_genes).map(|conn|
             (self.conn_genes.get(conn).unwrap(), other.conn_genes.get(conn).unwrap())
         )
     }
 
-    fn disjoint_genes<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &conn::ConnGene<Self>> + 'a {
-        let min_innov = cmp::min(self.conn_genes.first().unwrap().innov(), other.conn_genes.first().unwrap().innov());
-        self.conn_genes.symmetric_difference(&other.conn_genes).filter(move |conn| conn.innov().le(&min_innov))
+    fn disjoint_genes<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a conn::ConnGene<Self>> + 'a {
+        let max_innov = self.shared_innov_range(other);
+        self.conn_genes.symmetric_difference(&other.conn_genes).filter(move |conn| max_innov.is_some_and(|max| conn.innov().le(&max)))
     }
 
-    fn excess_genes<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &conn::ConnGene<Self>> + 'a {
-        let min_innov = cmp::min(
-            self.conn_genes.first().unwrap().innov(),
-            other.conn_genes.first().unwrap().innov()
-        );
+    fn excess_genes<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a conn::ConnGene<Self>> + 'a {
+        let max_innov = self.shared_innov_range(other);
+        self.conn_genes.symmetric_difference(&other.conn_genes).filter(move |conn| !max_innov.is_some_and(|max| conn.innov().le(&max)))
+    }
+
+    /// The highest innovation number that both genomes have reached, past which genes count as excess.
+    fn shared_innov_range(&self, other: &Self) -> Option<u32> {
+        self.conn_genes.last().zip(other.conn_genes.last()).map(|(lhs, rhs)| cmp::min(lhs.innov(), rhs.innov()))
+    }
+
+    pub(crate) fn insert_conn(&mut self, conn: conn::ConnGene<Self>) {
+        conn.in_node().forward_mut(|forward| forward.insert(conn.clone()));
+        conn.out_node().backward_mut(|backward| backward.insert(conn.clone()));
+        self.conn_genes.insert(conn);
+    }
+
+    fn remove_conn(&mut self, conn: &conn::ConnGene<Self>) {
+        conn.in_node().forward_mut(|forward| forward.remove(conn));
+        conn.out_node().backward_mut(|backward| backward.remove(conn));
+        self.conn_genes.remove(conn);
+    }
+
+    fn remove_node(&mut self, node: &node::NodeGene<Self>) {
+        for conn in node.forward(|forward| forward.clone()).union(&node.backward(|backward| backward.clone())) {
+            self.remove_conn(conn);
+        }
+
+        self.hidden_nodes.remove(node);
+    }
+
+    /// Removes the hidden nodes, along with their connections, that a deletion left without any incoming or without any
+    /// outgoing connection, and then the nodes that this strands in turn. `stranded` holds the nodes that just lost a
+    /// connection, each with whether it was an incoming one. Hidden nodes that never had incoming connections, such as
+    /// bias nodes, are kept as long as they feed something. Disabled connections count, since they may be re-enabled.
+    fn remove_stranded(&mut self, mut stranded: Vec<(node::NodeGene<Self>, bool)>) {
+        while let Some((node, lost_incoming)) = stranded.pop() {
+            if !self.hidden_nodes.contains(&node) {
+                continue;
+            }
+
+            let empty = match lost_incoming {
+                true => node.backward(|backward| backward.is_empty()),
+                false => node.forward(|forward| forward.is_empty()),
+            };
+
+            if empty {
+                stranded.extend(node.backward(|backward| backward.iter().map(|conn| (conn.in_node(), false)).collect::<Vec<_>>()));
+                stranded.extend(node.forward(|forward| forward.iter().map(|conn| (conn.out_node(), true)).collect::<Vec<_>>()));
+                self.remove_node(&node);
+            }
+        }
+    }
+
+    /// Returns every node reachable from `node` through the genome's connections, enabled or not.
+    fn descendants(&self, node: &node::NodeGene<Self>) -> BTreeSet<node::NodeGene<Self>> {
+        let mut descendants = BTreeSet::new();
+        let mut stack = vec![node.clone()];
+
+        while let Some(node) = stack.pop() {
+            for conn in self.conn_genes.iter().filter(|conn| conn.in_node().eq(&node)) {
+                if descendants.insert(conn.out_node()) {
+                    stack.push(conn.out_node());
+                }
+            }
+        }
+
+        descendants
+    }
+
+    /// Lists what changed from `self` to `other`, such as what a mutation or crossover did to a parent. Weight changes
+    /// of at most `weight_threshold` are left out.
+    pub fn diff(&self, other: &Self, weight_threshold: f32) -> GenomeDiff {
+        let (mut added_conns, mut removed_conns) = (Vec::new(), Vec::new());
+
+        for conn in self.disjoint_genes(other).chain(self.excess_genes(other)) {
+            match self.conn_genes.contains(conn) {
+                true => removed_conns.push(conn.innov()),
+                false => added_conns.push(conn.innov()),
+            }
+        }
+
+        added_conns.sort();
+        removed_conns.sort();
+
+        let matching = self.matching_genes(other).collect::<Vec<_>>();
+
+        GenomeDiff {
+            added_conns,
+            removed_conns,
+            enabled_changes: matching.iter()
+                .filter(|(before, after)| before.enabled().ne(&after.enabled()))
+                .map(|(_, after)| (after.innov(), after.enabled()))
+                .collect(),
+            weight_changes: matching.iter()
+                .filter(|(before, after)| (after.weight() - before.weight()).abs() > weight_threshold)
+                .map(|(before, after)| (after.innov(), before.weight(), after.weight()))
+                .collect(),
+            added_nodes: other.hidden_nodes.difference(&self.hidden_nodes).map(|node| node.id()).collect(),
+            removed_nodes: self.hidden_nodes.difference(&other.hidden_nodes).map(|node| node.id()).collect(),
+        }
+    }
+
+    /// Builds a genome from hidden node ids and `(in_node, out_node, weight, enabled)` connections that have already been
+    /// validated, drawing innovation numbers from the config history.
+    pub(crate) fn from_genes(config: &config::Config<Self>, hidden: &[u32], conns: &[(u32, u32, f32, bool)]) -> Self {
+        let mut genome = Self {
+            conn_genes: BTreeSet::new(),
+            input_nodes: (0..config.input_len()).map(|i| node::NodeGene::new_input(i as u32)).collect(),
+            hidden_nodes: hidden.iter().map(|&id| node::NodeGene::new_hidden(id)).collect(),
+            output_nodes: (0..config.output_len()).map(|i| node::NodeGene::new_output((config.input_len() + i) as u32)).collect(),
+            fitness: None,
+            objectives: Vec::new(),
+            lineage: Lineage::new(config.genome_id(), Vec::new(), 0),
+        };
+
+        let nodes = BTreeMap::from_iter(genome.input_nodes.iter().chain(&genome.hidden_nodes).chain(genome.output_nodes.iter())
+            .map(|node| (node.id(), node.clone())));
+
+        for &(in_node, out_node, weight, enabled) in conns {
+            let (in_node, out_node) = (nodes[&in_node].clone(), nodes[&out_node].clone());
+            let conn = conn::ConnGene::new(in_node.clone(), out_node.clone(), weight, config.innov(in_node, out_node));
+            conn.set_enabled(enabled);
+            genome.insert_conn(conn);
+        }
+
+        genome
+    }
+
+    /// Compiles the genome into a flat network, ordering its nodes topologically and dropping disabled connections.
+    pub fn compile(&self, config: &config::Config<Self>) -> Network<<config::Config<Self> as Config<Self>>::Activation> {
+        let enabled = self.conn_genes.iter().filter(|conn| conn.enabled()).collect::<Vec<_>>();
+
+        let mut in_degree = BTreeMap::from_iter(self.hidden_nodes.iter().chain(self.output_nodes.iter()).map(|node|
+            (node.clone(), enabled.iter().filter(|conn| conn.out_node().eq(node) && !self.input_nodes.contains(&conn.in_node())).count())
+        ));
+
+        let mut ready = in_degree.iter().filter(|(_, degree)| degree.eq(&&0)).map(|(node, _)| node.clone()).collect::<VecDeque<_>>();
+        let mut order = Vec::with_capacity(in_degree.len());
+
+        while let Some(node) = ready.pop_front() {
+            for conn in enabled.iter().filter(|conn| conn.in_node().eq(&node)) {
+                let degree = in_degree.get_mut(&conn.out_node()).unwrap();
+                *degree -= 1;
+
+                if *degree == 0 {
+                    ready.push_back(conn.out_node());
+                }
+            }
+
+            order.push(node);
+        }
+
+        assert_eq!(order.len(), in_degree.len(), "cannot compile a genome that contains a cycle");
+
+        let index = BTreeMap::from_iter(self.input_nodes.iter().chain(order.iter()).cloned().enumerate().map(|(i, node)| (node, i)));
+
+        let nodes = order.iter().map(|node| enabled.iter()
+            .filter(|conn| conn.out_node().eq(node))
+            .map(|conn| (index[&conn.in_node()], conn.weight()))
+            .collect()
+        ).collect();
+
+        let outputs = self.output_nodes.iter().map(|node| index[node]).collect();
+
+        Network::new(config.activation(), config.input_len(), outputs, nodes)
+    }
+
+    /// Returns a copy of the genome without disabled connections, hidden nodes that have no path to an output, and
+    /// hidden nodes that have no path from an input.
+    ///
+    /// A hidden node with no path from an input still feeds a constant into the rest of the network, so those constants
+    /// are folded into a single hidden node without incoming connections, reusing one of the constant nodes. The pruned
+    /// genome computes the same outputs as the original, up to floating point rounding, and pruning it again changes
+    /// nothing.
+    pub fn pruned(&self, config: &config::Config<Self>) -> Self {
+        let mut genome = self.clone();
+
+        for conn in genome.conn_genes.iter().filter(|conn| !conn.enabled()).cloned().collect::<Vec<_>>() {
+            genome.remove_conn(&conn);
+        }
+
+        let useful = reachable(genome.output_nodes.iter().cloned(), |node| node.backward(|backward| backward.iter().map(|conn| conn.in_node()).collect()));
+
+        for node in genome.hidden_nodes.iter().filter(|node| !useful.contains(node)).cloned().collect::<Vec<_>>() {
+            genome.remove_node(&node);
+        }
+
+        let live = reachable(genome.input_nodes.iter().cloned(), |node| node.forward(|forward| forward.iter().map(|conn| conn.out_node()).collect()));
+        let constants = genome.hidden_nodes.iter().filter(|node| !live.contains(node)).cloned().collect::<Vec<_>>();
+        let activation = config.activation();
+
+        // A lone constant node without incoming connections is what an earlier pruning folded the constants into.
+        if let [source] = constants.as_slice() {
+            if source.backward(|backward| backward.is_empty()) && activation.activate(0.0) != 0.0 {
+                return genome;
+            }
+        }
+
+        // Every node feeding a constant node is constant as well, so the values can be filled in from the sources on.
+        let mut values = BTreeMap::new();
+
+        while values.len() < constants.len() {
+            for node in constants.iter() {
+                if values.contains_key(node) {
+                    continue;
+                }
+
+                let sum = node.backward(|backward| backward.iter()
+                    .map(|conn| values.get(&conn.in_node()).map(|value| value * conn.weight()))
+                    .sum::<Option<f32>>());
+
+                if let Some(sum) = sum {
+                    values.insert(node.clone(), activation.activate(sum));
+                }
+            }
+        }
+
+        let mut biases = BTreeMap::new();
+
+        for conn in genome.conn_genes.iter().filter(|conn| values.contains_key(&conn.in_node()) && !values.contains_key(&conn.out_node())) {
+            *biases.entry(conn.out_node()).or_insert(0.0) += values[&conn.in_node()] * conn.weight();
+        }
+
+        for node in constants.iter() {
+            genome.remove_node(node);
+        }
+
+        biases.retain(|_, bias| *bias != 0.0);
+
+        // The lone constant node outputs the activation of zero, so its weights are scaled to reproduce each bias. If
+        // that activation is zero, every constant was zero and no bias survives. The constants were fed by at least one
+        // node without incoming connections, which already outputs that activation, so the lone node reuses its id.
+        if !biases.is_empty() {
+            let source = constants.iter().find(|node| node.backward(|backward| backward.is_empty())).unwrap();
+            let source = node::NodeGene::new_hidden(source.id());
+            let scale = activation.activate(0.0);
+
+            for (out_node, bias) in biases {
+                genome.insert_conn(conn::ConnGene::new(source.clone(), out_node.clone(), bias / scale, config.innov(source.clone(), out_node)));
+            }
+
+            genome.hidden_nodes.insert(source);
+        }
 
-        self.conn_genes.symmetric_difference(&other.conn_genes).collect::<Vec<_>>()
-            .into_iter().rev().take_while(move |conn| conn.innov().gt(&min_innov))
+        genome
+    }
+}
+
+// Genes live behind `Arc`s, so a derived clone would let offspring mutate the genes of their parents. Every node and
+// connection is rebuilt instead, keeping the ids and innovation numbers that line genes up between genomes.
+impl Clone for FeedForwardGenome {
+    fn clone(&self) -> Self {
+        let mut genome = Self {
+            conn_genes: BTreeSet::new(),
+            input_nodes: self.input_nodes.iter().map(|node| node::NodeGene::new_input(node.id())).collect(),
+            hidden_nodes: self.hidden_nodes.iter().map(|node| node::NodeGene::new_hidden(node.id())).collect(),
+            output_nodes: self.output_nodes.iter().map(|node| node::NodeGene::new_output(node.id())).collect(),
+            fitness: self.fitness,
+            objectives: self.objectives.clone(),
+            lineage: self.lineage.clone(),
+        };
+
+        let nodes = BTreeMap::from_iter(genome.input_nodes.iter().chain(&genome.hidden_nodes).chain(genome.output_nodes.iter())
+            .map(|node| (node.id(), node.clone())));
+
+        for conn in self.conn_genes.iter() {
+            let new_conn = conn::ConnGene::new(nodes[&conn.in_node().id()].clone(), nodes[&conn.out_node().id()].clone(), conn.weight(), conn.innov());
+            new_conn.set_enabled(conn.enabled());
+            genome.insert_conn(new_conn);
+        }
+
+        genome
+    }
+}
+
+// Genes compare by pointer, so genomes are compared and hashed by the content of their genes instead.
+impl PartialEq for FeedForwardGenome {
+    fn eq(&self, other: &Self) -> bool {
+        fingerprint::structural_eq(self, other, None)
+    }
+}
+
+impl Eq for FeedForwardGenome { }
+
+impl Hash for FeedForwardGenome {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(fingerprint::content_hash(self, None));
     }
 }
 
@@ -42,109 +323,212 @@ impl traits::Genome for FeedForwardGenome {
     type NodeGene = node::NodeGene<Self>;
 
     fn minimal(config: &Self::Config) -> Self {
-        Self {
+        let mut rng = rand::thread_rng();
+        let first_hidden = config.input_len() + config.output_len();
+
+        let hidden_len = match config.initial_topology() {
+            InitialTopology::Hidden(hidden_len) => hidden_len,
+            _ => 0,
+        };
+
+        let mut genome = Self {
             conn_genes: BTreeSet::new(),
-            input_nodes: iter::repeat(node::NodeGene::new_input()).take(config.input_len()).collect(),
-            hidden_nodes: BTreeSet::new(),
-            output_nodes: iter::repeat(node::NodeGene::new_output()).take(config.output_len()).collect(),
+            input_nodes: (0..config.input_len()).map(|i| node::NodeGene::new_input(i as u32)).collect(),
+            hidden_nodes: (0..hidden_len).map(|i| node::NodeGene::new_hidden((first_hidden + i) as u32)).collect(),
+            output_nodes: (0..config.output_len()).map(|i| node::NodeGene::new_output((config.input_len() + i) as u32)).collect(),
             fitness: None,
+            objectives: Vec::new(),
+            lineage: Lineage::new(config.genome_id(), Vec::new(), 0),
+        };
+
+        let (inputs, hidden, outputs) = (
+            genome.input_nodes.to_vec(),
+            genome.hidden_nodes.iter().cloned().collect::<Vec<_>>(),
+            genome.output_nodes.to_vec(),
+        );
+
+        let layers = match config.initial_topology() {
+            InitialTopology::Unconnected => vec![],
+            InitialTopology::FullyConnected | InitialTopology::Partial(_) => vec![(&inputs, &outputs)],
+            InitialTopology::Hidden(_) => vec![(&inputs, &hidden), (&hidden, &outputs)],
+        };
+
+        for (in_nodes, out_nodes) in layers {
+            for (in_node, out_node) in in_nodes.iter().flat_map(|in_node| out_nodes.iter().map(move |out_node| (in_node, out_node))) {
+                if let InitialTopology::Partial(fraction) = config.initial_topology() {
+                    if !rng.gen_bool(fraction) {
+                        continue;
+                    }
+                }
+
+                let innov = config.innov(in_node.clone(), out_node.clone());
+                genome.insert_conn(conn::ConnGene::new(in_node.clone(), out_node.clone(), clamp_weight(config.weight_init().sample(&mut rng), config), innov));
+            }
         }
+
+        genome
+    }
+
+    fn input_nodes(&self) -> impl Iterator<Item = &Self::NodeGene> {
+        self.input_nodes.iter()
+    }
+
+    fn hidden_nodes(&self) -> impl Iterator<Item = &Self::NodeGene> {
+        self.hidden_nodes.iter()
+    }
+
+    fn output_nodes(&self) -> impl Iterator<Item = &Self::NodeGene> {
+        self.output_nodes.iter()
+    }
+
+    fn conn_genes(&self) -> impl Iterator<Item = &Self::ConnGene> {
+        self.conn_genes.iter()
     }
 
-    fn add_conn_mut(&mut self, config: &Self::Config) -> Self::ConnGene {
-        let mut possible_in_nodes = self.input_nodes.iter().chain(&self.hidden_nodes).cloned().collect::<Vec<_>>();
-        assert_ne!(possible_in_nodes.len(), 0);
-        possible_in_nodes.shuffle(&mut rand::thread_rng());
+    fn add_conn_mut(&mut self, config: &Self::Config) -> Option<Self::ConnGene> {
+        if self.conn_genes.len() >= config.max_conns() {
+            return None;
+        }
+
+        // Connecting a node back to one of its own ancestors would close a cycle, so the candidate pairs exclude every
+        // in node that is already reachable from the out node, including through disabled connections that may later be
+        // re-enabled.
+        let candidates = self.output_nodes.iter().chain(&self.hidden_nodes).flat_map(|out_node| {
+            let descendants = self.descendants(out_node);
 
-        let mut possible_out_nodes = self.output_nodes.iter().chain(&self.hidden_nodes).cloned().collect::<Vec<_>>();
-        assert_ne!(possible_out_nodes.len(), 0);
-        possible_out_nodes.shuffle(&mut rand::thread_rng());
+            self.input_nodes.iter().chain(&self.hidden_nodes)
+                .filter(move |in_node| in_node.ne(&out_node) && !descendants.contains(in_node))
+                .filter(|in_node| !self.conn_genes.iter().any(|conn| conn.in_node().eq(in_node) && conn.out_node().eq(out_node)))
+                .map(|in_node| (in_node.clone(), out_node.clone()))
+        }).collect::<Vec<_>>();
 
-        let in_node = possible_in_nodes.iter().find(|node| possible_out_nodes.iter().count()
-            .saturating_sub(self.hidden_nodes.contains(node) as usize)
-            .saturating_sub(node.forward(|forward| forward.len())).gt(&0)
-        ).unwrap();
-        
-        let out_node = possible_out_nodes.iter().find(|node|
-            node.backward(|backward| !backward.iter().any(|conn| conn.in_node().eq(in_node)))
-        ).unwrap();
+        let (in_node, out_node) = candidates.choose(&mut rand::thread_rng())?;
 
-        let new_conn = conn::ConnGene::new(in_node.clone(), out_node.clone(), rand::random(), config.innov(in_node.clone(), out_node.clone()));
-        (new_conn.in_node() as Self::NodeGene).forward_mut(|forward| forward.insert(new_conn.clone()));
-        new_conn.out_node().backward_mut(|backward| backward.insert(new_conn.clone()));
-        self.conn_genes.insert(new_conn.clone());
+        let weight = clamp_weight(config.weight_init().sample(&mut rand::thread_rng()), config);
+        let new_conn = conn::ConnGene::new(in_node.clone(), out_node.clone(), weight, config.innov(in_node.clone(), out_node.clone()));
+        self.insert_conn(new_conn.clone());
 
-        new_conn.clone()
+        Some(new_conn)
     }
 
-    fn add_node_mut(&mut self, config: &Self::Config) -> Self::NodeGene {
-        assert_ne!(self.conn_genes.len(), 0);
+    fn add_node_mut(&mut self, config: &Self::Config) -> Option<Self::NodeGene> {
+        // Splitting a connection disables it rather than removing it, so the genome gains two connection genes.
+        if self.hidden_nodes.len() >= config.max_hidden_nodes() || self.conn_genes.len() + 2 > config.max_conns() {
+            return None;
+        }
 
-        let old_conn = self.conn_genes.iter().filter(|conn| conn.enabled()).choose(&mut rand::thread_rng()).unwrap();
+        let old_conn = self.conn_genes.iter().filter(|conn| conn.enabled()).choose(&mut rand::thread_rng())?.clone();
         old_conn.set_enabled(false);
 
-        let new_node = node::NodeGene::new_hidden();
+        let new_node = node::NodeGene::new_hidden(config.node_id());
 
-        let conn_a = conn::ConnGene::new(old_conn.in_node(), new_node.clone(), 1.0, config.innov(old_conn.in_node(), new_node.clone()));
+        let conn_a = conn::ConnGene::new(old_conn.in_node(), new_node.clone(), clamp_weight(1.0, config), config.innov(old_conn.in_node(), new_node.clone()));
         let conn_b = conn::ConnGene::new(new_node.clone(), old_conn.out_node(), old_conn.weight(), config.innov(new_node.clone(), old_conn.out_node()));
 
-        old_conn.in_node().forward_mut(|forward| forward.insert(conn_a.clone()));
-        old_conn.out_node().backward_mut(|backward| backward.insert(conn_b.clone()));
+        self.insert_conn(conn_a);
+        self.insert_conn(conn_b);
+
+        self.hidden_nodes.insert(new_node.clone());
+
+        Some(new_node)
+    }
 
-        new_node.forward_mut(|forward| forward.insert(conn_b.clone()));
-        new_node.backward_mut(|backward| backward.insert(conn_a.clone()));
+    fn mut_conn_weight(&mut self, config: &Self::Config) -> Option<Self::ConnGene> {
+        // The chance that a weight is nudged rather than replaced, and how far it can be nudged.
+        const PERTURB_CHANCE: f64 = 0.9;
+        const PERTURB_POWER: f32 = 0.5;
 
-        self.conn_genes.insert(conn_a.clone());
-        self.conn_genes.insert(conn_b.clone());
+        let mut rng = rand::thread_rng();
+        let conn = self.conn_genes.iter().choose(&mut rng)?;
 
-        self.hidden_nodes.insert(new_node.clone());
+        match rng.gen_bool(PERTURB_CHANCE) {
+            true => conn.set_weight(clamp_weight(conn.weight() + rng.gen_range(-PERTURB_POWER..=PERTURB_POWER), config)),
+            false => conn.set_weight(clamp_weight(config.weight_init().sample(&mut rng), config)),
+        }
 
-        new_node.clone()
+        Some(conn.clone())
     }
 
-    fn mut_conn_weight(&mut self, _config: &Self::Config) -> Self::ConnGene {
-        todo!()
+    fn jitter_weights(&mut self, power: f32, config: &Self::Config) {
+        let mut rng = rand::thread_rng();
+
+        for conn in self.conn_genes.iter() {
+            conn.set_weight(clamp_weight(conn.weight() + rng.gen_range(-power..=power), config));
+        }
     }
 
-    fn activate(&self, input: Vec<f32>, config: &Self::Config) -> Vec<f32> {
-        assert_eq!(input.len(), config.input_len());
+    fn del_conn_mut(&mut self, _config: &Self::Config) -> Option<Self::ConnGene> {
+        let conn = self.conn_genes.iter().choose(&mut rand::thread_rng())?.clone();
 
-        let mut map = BTreeMap::from_iter(self.input_nodes.iter().enumerate().map(|(i, node)|
-            (node.clone(), input.get(i).cloned().unwrap())
-        ));
+        self.remove_conn(&conn);
+        self.remove_stranded(vec![(conn.in_node(), false), (conn.out_node(), true)]);
 
-        while let Some((node, value)) = map.first_entry().map(|entry| (entry.key().clone(), entry.get().clone())) {
-            let value = config.activation().activate(value);
+        Some(conn)
+    }
 
-            for conn in node.forward(|forward| forward.clone()).iter().filter(|conn| conn.enabled()) {
-                map.entry(conn.out_node()).or_insert(0.0).add_assign(value * conn.weight());
-            }
+    fn del_node_mut(&mut self, _config: &Self::Config) -> Option<Self::NodeGene> {
+        let node = self.hidden_nodes.iter().choose(&mut rand::thread_rng())?.clone();
 
-            map.remove(&node);
-        }
+        let stranded = node.backward(|backward| backward.iter().map(|conn| (conn.in_node(), false)).collect::<Vec<_>>()).into_iter()
+            .chain(node.forward(|forward| forward.iter().map(|conn| (conn.out_node(), true)).collect::<Vec<_>>()))
+            .collect();
+
+        self.remove_node(&node);
+        self.remove_stranded(stranded);
 
-        (0..config.output_len())
-            .map(|i| config.activation().activate(map.get(self.output_nodes.get(i).unwrap()).cloned().unwrap()))
-            .collect::<Vec<_>>().try_into().unwrap()
+        Some(node)
+    }
+
+    fn toggle_conn_mut(&mut self, _config: &Self::Config) -> Option<Self::ConnGene> {
+        let conn = self.conn_genes.iter().choose(&mut rand::thread_rng())?;
+        conn.set_enabled(!conn.enabled());
+
+        Some(conn.clone())
+    }
+
+    /// Compiles the genome anew on every call, which allocates and walks every connection once per node. Callers that
+    /// activate the same genome repeatedly, such as over every sample of a fitness evaluation, should call
+    /// [`FeedForwardGenome::compile`] once and reuse the [`Network`].
+    fn activate(&self, input: Vec<f32>, config: &Self::Config) -> Vec<f32> {
+        self.compile(config).activate(&input)
+    }
+
+    fn fitness(&self) -> Option<f32> {
+        self.fitness
     }
 
     fn set_fitness(&mut self, fitness: f32, _: &Self::Config) {
         self.fitness = Some(fitness);
     }
+
+    fn objectives(&self) -> &[f32] {
+        &self.objectives
+    }
+
+    fn set_objectives(&mut self, objectives: Vec<f32>, _: &Self::Config) {
+        self.objectives = objectives;
+    }
     
-    fn comp_dist(&self, other: &Self, config: &Self::Config) -> f32 {
-        assert!(self.fitness.is_some());
-        assert!(other.fitness.is_some());
+    fn lineage(&self) -> &Lineage {
+        &self.lineage
+    }
+
+    fn set_lineage(&mut self, lineage: Lineage, _: &Self::Config) {
+        self.lineage = lineage;
+    }
 
-        let n = cmp::max(self.conn_genes.first().cloned().unwrap().innov(), other.conn_genes.first().cloned().unwrap().innov());
+    fn comp_dist(&self, other: &Self, config: &Self::Config) -> f32 {
+        let n = cmp::max(self.conn_genes.len(), other.conn_genes.len()).max(1);
 
         let (c1, c2, c3) = (config.c1(), config.c2(), config.c3());
 
-        let e = self.excess_genes(&other).count() as f32;
-        let d = self.disjoint_genes(&other).count() as f32;
+        let e = self.excess_genes(other).count() as f32;
+        let d = self.disjoint_genes(other).count() as f32;
 
-        let mut w = self.matching_genes(&other).map(|(lhs, rhs)| lhs.weight().sub(&rhs.weight())).sum::<f32>();
-        w /= self.matching_genes(&other).count() as f32;
+        let w = match self.matching_genes(other).count() {
+            0 => 0.0,
+            matching => self.matching_genes(other).map(|(lhs, rhs)| (lhs.weight() - rhs.weight()).abs()).sum::<f32>() / matching as f32,
+        };
 
         let mut dist = 0.0;
         dist += (c1 * e) / (n as f32);
@@ -154,9 +538,7 @@ impl traits::Genome for FeedForwardGenome {
         dist
     }
     
-    fn crossover(&self, other: &Self, _config: &Self::Config) -> Self {
-        // TODO: This works, but implementation is ugly. Clean it up in the future.
-
+    fn crossover(&self, other: &Self, config: &Self::Config) -> Self {
         assert!(self.fitness.is_some());
         assert!(other.fitness.is_some());
 
@@ -165,50 +547,47 @@ impl traits::Genome for FeedForwardGenome {
         // The chance that the child genome inherits a matching connection gene from the more fit parent.
         const MATCHING_PREFERENCE: f64 = 2.0 / 3.0;
 
-        let more_fit = cmp::max_by(self, other, |a, b| a.fitness.unwrap().total_cmp(&b.fitness.unwrap()));
-        let less_fit = cmp::min_by(self, other, |a, b| a.fitness.unwrap().total_cmp(&b.fitness.unwrap()));
+        // The child takes its structure from the more fit parent, or from `self` when both are equally fit, in which
+        // case matching genes are inherited from either parent with equal chance.
+        let (more_fit, less_fit, preference) = match self.fitness.unwrap().total_cmp(&other.fitness.unwrap()) {
+            cmp::Ordering::Less => (other, self, MATCHING_PREFERENCE),
+            cmp::Ordering::Greater => (self, other, MATCHING_PREFERENCE),
+            cmp::Ordering::Equal => (self, other, 0.5),
+        };
+
+        let mut child = more_fit.clone();
+        child.fitness = None;
+        child.objectives.clear();
+        child.lineage = Lineage::new(config.genome_id(), vec![self.lineage.id, other.lineage.id], cmp::max(self.lineage.generation, other.lineage.generation) + 1);
+
+        for (conn, choice) in child.matching_genes(less_fit).filter(|_| !rng.gen_bool(preference)) {
+            conn.set_weight(choice.weight());
+            conn.set_enabled(choice.enabled());
+        }
 
-        match self.fitness.unwrap().total_cmp(&other.fitness.unwrap()) {
-            cmp::Ordering::Less | cmp::Ordering::Greater => {
-                for key in more_fit.conn_genes.intersection(&less_fit.conn_genes) {
-                    let choice = match rng.gen_bool(MATCHING_PREFERENCE) {
-                        true => more_fit.conn_genes.get(key).unwrap(),
-                        false => less_fit.conn_genes.get(key).unwrap()
-                    };
+        for conn in child.conn_genes.iter() {
+            conn.set_weight(clamp_weight(conn.weight(), config));
+        }
 
-                    let conn = more_fit.conn_genes.get(key).unwrap();
-                    conn.set_weight(choice.weight());
-                    conn.set_enabled(choice.enabled());
-                }
+        child
+    }
+}
 
-                Self {
-                    conn_genes: more_fit.conn_genes.clone(),
-                    input_nodes: more_fit.input_nodes.clone(),
-                    hidden_nodes: more_fit.hidden_nodes.clone(),
-                    output_nodes: more_fit.output_nodes.clone(),
-                    fitness: None
-                }
-            },
-            cmp::Ordering::Equal => {
-                for key in self.conn_genes.intersection(&other.conn_genes) {
-                    let choice = match rng.gen() {
-                        true => self.conn_genes.get(key).unwrap(),
-                        false => other.conn_genes.get(key).unwrap()
-                    };
-
-                    let conn = self.conn_genes.get(key).unwrap();
-                    conn.set_weight(choice.weight());
-                    conn.set_enabled(choice.enabled());
-                }
+/// Returns every node reachable from `start` by repeatedly following `next`, including the starting nodes.
+fn reachable(start: impl Iterator<Item = node::NodeGene<FeedForwardGenome>>, next: impl Fn(&node::NodeGene<FeedForwardGenome>) -> Vec<node::NodeGene<FeedForwardGenome>>) -> BTreeSet<node::NodeGene<FeedForwardGenome>> {
+    let mut reached = BTreeSet::new();
+    let mut stack = start.collect::<Vec<_>>();
 
-                Self {
-                    conn_genes: self.conn_genes.clone(),
-                    input_nodes: self.input_nodes.clone(),
-                    hidden_nodes: self.hidden_nodes.clone(),
-                    output_nodes: self.output_nodes.clone(),
-                    fitness: None
-                }
-            }
+    while let Some(node) = stack.pop() {
+        if reached.insert(node.clone()) {
+            stack.extend(next(&node));
         }
     }
+
+    reached
+}
+
+fn clamp_weight(weight: f32, config: &config::Config<FeedForwardGenome>) -> f32 {
+    let bounds = config.weight_bounds();
+    weight.clamp(*bounds.start(), *bounds.end())
 }
\ No newline at end of file
@@ -0,0 +1,101 @@
+use std::iter;
+use crate::{Network, traits};
+use bevy::math::{Vec2, Vec3};
+
+/// Node coordinates for a HyperNEAT substrate, grouped into an input layer, any number of hidden layers and an output
+/// layer. Every layer is fully connected to the next one, and the weight of each of those connections is read from a
+/// CPPN evolved with the regular NEAT machinery.
+///
+/// The CPPN receives the source coordinates, the target coordinates and a bias of `1.0`, so it needs
+/// [`Substrate::cppn_input_len`] inputs and a single output. Genomes compile with the sigmoid activation, so CPPN
+/// outputs lie in `[0, 1]`. An output of `0.5` stands for no connection, lower outputs for negative weights and higher
+/// ones for positive weights.
+#[derive(Clone, Debug)]
+pub struct Substrate {
+    dimensions: usize,
+    layers: Vec<Vec<Vec3>>,
+}
+
+impl Substrate {
+    pub fn new_2d(input: Vec<Vec2>, hidden: Vec<Vec<Vec2>>, output: Vec<Vec2>) -> Self {
+        let layers = iter::once(input).chain(hidden).chain(iter::once(output))
+            .map(|layer| layer.into_iter().map(|point| point.extend(0.0)).collect())
+            .collect();
+
+        Self::new(2, layers)
+    }
+
+    pub fn new_3d(input: Vec<Vec3>, hidden: Vec<Vec<Vec3>>, output: Vec<Vec3>) -> Self {
+        Self::new(3, iter::once(input).chain(hidden).chain(iter::once(output)).collect())
+    }
+
+    fn new(dimensions: usize, layers: Vec<Vec<Vec3>>) -> Self {
+        assert!(layers.iter().all(|layer| !layer.is_empty()));
+        Self { dimensions, layers }
+    }
+
+    pub fn cppn_input_len(&self) -> usize {
+        2 * self.dimensions + 1
+    }
+
+    pub fn input_len(&self) -> usize {
+        self.layers.first().unwrap().len()
+    }
+
+    pub fn output_len(&self) -> usize {
+        self.layers.last().unwrap().len()
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.layers.iter().map(Vec::len).sum()
+    }
+
+    /// Queries a compiled CPPN for the weight of every connection between consecutive layers and builds the resulting
+    /// network.
+    ///
+    /// The CPPN output is mapped from `[0, 1]` to `[-1, 1]` by `2 * output - 1`, which assumes a CPPN with a sigmoid
+    /// output such as every compiled genome has. Connections whose magnitude does not exceed `weight_threshold` are
+    /// left out, and the remaining ones are scaled to at most `max_weight`.
+    pub fn query<A: traits::Activation>(&self, cppn: &mut Network<A>, activation: A, weight_threshold: f32, max_weight: f32) -> Network<A> {
+        assert_eq!(cppn.input_len(), self.cppn_input_len());
+        assert_eq!(cppn.output_len(), 1);
+        assert!((0.0..1.0).contains(&weight_threshold));
+
+        let mut nodes = Vec::with_capacity(self.node_count() - self.input_len());
+        let (mut queries, mut weights) = (Vec::new(), Vec::new());
+        let mut offset = 0;
+
+        for pair in self.layers.windows(2) {
+            let (sources, targets) = (&pair[0], &pair[1]);
+
+            queries.clear();
+
+            for target in targets {
+                for source in sources {
+                    queries.extend_from_slice(&source.to_array()[..self.dimensions]);
+                    queries.extend_from_slice(&target.to_array()[..self.dimensions]);
+                    queries.push(1.0);
+                }
+            }
+
+            weights.resize(targets.len() * sources.len(), 0.0);
+            cppn.activate_batch_into(&queries, &mut weights);
+
+            for row in weights.chunks_exact(sources.len()) {
+                nodes.push(row.iter().enumerate().filter_map(|(source, &output)| {
+                    let weight = 2.0 * output - 1.0;
+
+                    (weight.abs() > weight_threshold).then(|| {
+                        (offset + source, weight.signum() * (weight.abs() - weight_threshold) / (1.0 - weight_threshold) * max_weight)
+                    })
+                }).collect());
+            }
+
+            offset += sources.len();
+        }
+
+        let outputs = (offset..offset + self.output_len()).collect();
+
+        Network::new(activation, self.input_len(), outputs, nodes)
+    }
+}
\ No newline at end of file
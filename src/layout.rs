@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+use crate::traits::{self, ConnGene, NodeGene};
+use bevy::math::Vec2;
+
+// How many alternating downward and upward barycenter sweeps are tried when reducing edge crossings.
+const SWEEPS: usize = 8;
+
+/// 2D positions for every node of a genome, for drawing it.
+///
+/// Nodes are layered by their longest path from the inputs, with the inputs in the first layer and the outputs in the
+/// last. Hidden layers are reordered with the barycenter heuristic to reduce edge crossings. Positions are normalized
+/// so that layers run from `x = 0` to `x = 1` and each layer is spread evenly over `y` in `(0, 1)`.
+#[derive(Clone, Debug)]
+pub struct Layout {
+    edges: Vec<(u32, u32)>,
+    layers: Vec<Vec<u32>>,
+    positions: BTreeMap<u32, Vec2>,
+}
+
+impl Layout {
+    pub fn new<G: traits::Genome>(genome: &G) -> Self {
+        let edges = genome.conn_genes().map(|conn| (conn.in_node().id(), conn.out_node().id())).collect::<Vec<_>>();
+        let mut depths = BTreeMap::from_iter(genome.input_nodes().chain(genome.hidden_nodes()).map(|node| (node.id(), 0)));
+
+        // Relax longest-path depths at most once per node, which also bounds the work if the genome has a cycle.
+        for _ in 0..depths.len() {
+            let mut changed = false;
+
+            for &(in_node, out_node) in edges.iter() {
+                if let (Some(&depth), Some(&out_depth)) = (depths.get(&in_node), depths.get(&out_node)) {
+                    if out_depth < depth + 1 {
+                        depths.insert(out_node, depth + 1);
+                        changed = true;
+                    }
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        for node in genome.hidden_nodes() {
+            depths.entry(node.id()).and_modify(|depth| *depth = (*depth).max(1));
+        }
+
+        let output_depth = depths.values().max().map_or(1, |depth| depth + 1);
+        let mut layers = vec![Vec::new(); output_depth + 1];
+
+        for node in genome.input_nodes() {
+            layers[0].push(node.id());
+        }
+
+        for node in genome.hidden_nodes() {
+            layers[depths[&node.id()]].push(node.id());
+        }
+
+        layers[output_depth].extend(genome.output_nodes().map(|node| node.id()));
+        layers.retain(|layer| !layer.is_empty());
+
+        let mut layout = Self { edges, layers, positions: BTreeMap::new() };
+        layout.place();
+        layout.reduce_crossings();
+        layout
+    }
+
+    pub fn layers(&self) -> &[Vec<u32>] {
+        &self.layers
+    }
+
+    pub fn position(&self, node: u32) -> Option<Vec2> {
+        self.positions.get(&node).copied()
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (u32, Vec2)> + '_ {
+        self.positions.iter().map(|(&node, &position)| (node, position))
+    }
+
+    /// Counts the pairs of connections whose straight lines cross, including connections that skip layers. Lines that
+    /// only meet at a shared node are not counted.
+    pub fn crossings(&self) -> usize {
+        let spans = self.edges.iter().filter_map(|(in_node, out_node)| Some((self.position(*in_node)?, self.position(*out_node)?))).collect::<Vec<_>>();
+
+        // Two lines cross when the ends of each lie strictly on opposite sides of the other.
+        let sides = |(a, b): (Vec2, Vec2), c: Vec2, d: Vec2| (b - a).perp_dot(c - a) * (b - a).perp_dot(d - a) < 0.0;
+
+        spans.iter().enumerate().map(|(i, &(a, b))| spans[i + 1..].iter()
+            .filter(|&&(c, d)| sides((a, b), c, d) && sides((c, d), a, b))
+            .count()
+        ).sum()
+    }
+
+    fn place(&mut self) {
+        let last = (self.layers.len() - 1).max(1) as f32;
+
+        for (x, layer) in self.layers.iter().enumerate() {
+            for (y, &node) in layer.iter().enumerate() {
+                self.positions.insert(node, Vec2::new(x as f32 / last, (y as f32 + 0.5) / layer.len() as f32));
+            }
+        }
+    }
+
+    /// Sorts each hidden layer by the mean height of its neighbors in the layers already swept, alternating direction,
+    /// and keeps the ordering with the fewest crossings. Input and output layers keep their order.
+    fn reduce_crossings(&mut self) {
+        let mut best = (self.crossings(), self.layers.clone());
+
+        for sweep in 0..SWEEPS {
+            let downward = sweep % 2 == 0;
+            let mut hidden = (1..self.layers.len().saturating_sub(1)).collect::<Vec<_>>();
+
+            if !downward {
+                hidden.reverse();
+            }
+
+            for i in hidden {
+                let barycenters = BTreeMap::from_iter(self.layers[i].iter().map(|&node| {
+                    let heights = self.edges.iter()
+                        .filter_map(|&(in_node, out_node)| match downward {
+                            true => (out_node == node).then_some(in_node),
+                            false => (in_node == node).then_some(out_node),
+                        })
+                        .filter_map(|neighbor| self.position(neighbor))
+                        .map(|position| position.y)
+                        .collect::<Vec<_>>();
+
+                    let barycenter = match heights.is_empty() {
+                        true => self.positions[&node].y,
+                        false => heights.iter().sum::<f32>() / heights.len() as f32,
+                    };
+
+                    (node, barycenter)
+                }));
+
+                self.layers[i].sort_by(|a, b| barycenters[a].total_cmp(&barycenters[b]));
+                self.place();
+            }
+
+            let crossings = self.crossings();
+
+            if crossings < best.0 {
+                best = (crossings, self.layers.clone());
+            }
+        }
+
+        self.layers = best.1;
+        self.place();
+    }
+}
\ No newline at end of file
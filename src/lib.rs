@@ -1,4 +1,7 @@
 #![allow(dead_code)]
+// Genes are ordered by immutable ids and innovation numbers, so the interior mutability behind them never affects
+// their position in ordered collections.
+#![allow(clippy::mutable_key_type)]
 
 // TODO: Remove the trait bounds on the traits that are not neccessary for the population struct. Instead, specify these
 // triat bounds on the other trait implementations that require such bounds.
@@ -6,18 +9,46 @@
 // the config implementation and remove the constants from the current implementation in the future.
 
 mod activations;
+mod builder;
 mod config;
 mod conn;
+mod diff;
+mod dot;
+mod fingerprint;
 mod genome;
+mod hyperneat;
+mod layout;
+mod lineage;
+mod metrics;
+mod network;
 mod node;
+mod novelty;
+mod pareto;
 mod population;
+mod selection;
+mod speciation;
+mod validate;
 
 pub use activations::*;
+pub use builder::*;
 pub use config::*;
 pub use conn::*;
+pub use diff::*;
+pub use dot::*;
+pub use fingerprint::*;
 pub use genome::*;
+pub use hyperneat::*;
+pub use layout::*;
+pub use lineage::*;
+pub use metrics::*;
+pub use network::*;
 pub use node::*;
+pub use novelty::*;
+pub use pareto::*;
 pub use population::*;
+pub use selection::*;
+pub use speciation::*;
+pub use validate::*;
 
 pub mod traits {
     use std::fmt::Debug;
@@ -29,11 +60,32 @@ pub mod traits {
         type NodeGene: NodeGene;
 
         fn minimal(config: &Self::Config) -> Self;
-        fn add_conn_mut(&mut self, config: &Self::Config) -> Self::ConnGene;
-        fn add_node_mut(&mut self, config: &Self::Config) -> Self::NodeGene;
-        fn mut_conn_weight(&mut self, config: &Self::Config) -> Self::ConnGene;
+        fn input_nodes(&self) -> impl Iterator<Item = &Self::NodeGene>;
+        fn hidden_nodes(&self) -> impl Iterator<Item = &Self::NodeGene>;
+        fn output_nodes(&self) -> impl Iterator<Item = &Self::NodeGene>;
+        fn conn_genes(&self) -> impl Iterator<Item = &Self::ConnGene>;
+        /// Returns `None` when every connection that keeps the genome valid already exists, or when the config's
+        /// connection limit has been reached.
+        fn add_conn_mut(&mut self, config: &Self::Config) -> Option<Self::ConnGene>;
+        /// Returns `None` when there is no enabled connection to split, or when a config limit has been reached.
+        fn add_node_mut(&mut self, config: &Self::Config) -> Option<Self::NodeGene>;
+        /// Returns `None` when the genome has no connections.
+        fn mut_conn_weight(&mut self, config: &Self::Config) -> Option<Self::ConnGene>;
+        /// Nudges every connection weight by its own uniform amount in `[-power, power]`.
+        fn jitter_weights(&mut self, power: f32, config: &Self::Config);
+        /// Returns `None` when the genome has no connections.
+        fn del_conn_mut(&mut self, config: &Self::Config) -> Option<Self::ConnGene>;
+        /// Returns `None` when the genome has no hidden nodes.
+        fn del_node_mut(&mut self, config: &Self::Config) -> Option<Self::NodeGene>;
+        /// Returns `None` when the genome has no connections.
+        fn toggle_conn_mut(&mut self, config: &Self::Config) -> Option<Self::ConnGene>;
         fn activate(&self, input: Vec<f32>, config: &Self::Config) -> Vec<f32>;
+        fn fitness(&self) -> Option<f32>;
         fn set_fitness(&mut self, fitness: f32, config: &Self::Config);
+        fn objectives(&self) -> &[f32];
+        fn set_objectives(&mut self, objectives: Vec<f32>, config: &Self::Config);
+        fn lineage(&self) -> &crate::Lineage;
+        fn set_lineage(&mut self, lineage: crate::Lineage, config: &Self::Config);
         fn comp_dist(&self, other: &Self, config: &Self::Config) -> f32;
         fn crossover(&self, other: &Self, config: &Self::Config) -> Self;
     }
@@ -42,6 +94,15 @@ pub mod traits {
         type Activation: Activation;
 
         fn innov(&self, in_node: G::NodeGene, out_node: G::NodeGene) -> u32;
+        fn node_id(&self) -> u32;
+        fn genome_id(&self) -> u32;
+        /// Records the node ids, innovation numbers and genome ids of genomes that were not evolved with this config, so
+        /// that new genes never reuse them for different structure and new genomes never reuse the ids.
+        ///
+        /// Fails without recording anything when an innovation number of a genome disagrees with the history or with
+        /// another of the genomes, because they have another number for the same pair of nodes or another pair for the
+        /// same number.
+        fn register(&self, genomes: &[G]) -> Result<(), Vec<crate::InnovConflict>>;
         fn activation(&self) -> Self::Activation;
         fn input_len(&self) -> usize;
         fn output_len(&self) -> usize;
@@ -50,6 +111,38 @@ pub mod traits {
         fn c1(&self) -> f32;
         fn c2(&self) -> f32;
         fn c3(&self) -> f32;
+        fn max_stagnation(&self) -> Option<usize>;
+        fn species_elitism(&self) -> usize;
+        fn elitism(&self) -> usize;
+        fn survival_threshold(&self) -> f32;
+        fn min_species_size(&self) -> usize;
+        fn species_target(&self) -> Option<crate::SpeciesTarget>;
+        fn selection(&self) -> &dyn Selection<G>;
+        fn speciation(&self) -> &dyn Speciation<G>;
+        fn interspecies_mating_rate(&self) -> f64;
+        fn initial_topology(&self) -> crate::InitialTopology;
+        fn weight_init(&self) -> crate::WeightInit;
+        fn weight_bounds(&self) -> std::ops::RangeInclusive<f32>;
+        fn max_hidden_nodes(&self) -> usize;
+        fn max_conns(&self) -> usize;
+        fn node_penalty(&self) -> f32;
+        fn conn_penalty(&self) -> f32;
+    }
+
+    /// Picks a parent among the members of a species that may reproduce, which are ranked from most to least fit.
+    pub trait Selection<G: Genome>: Send + Sync {
+        /// Draws every random choice from `rng`, so that a seeded generator gives reproducible picks.
+        fn select<'a>(&self, parents: &[&'a G], rng: &mut dyn rand::RngCore) -> &'a G;
+    }
+
+    pub trait Speciation<G: Genome>: Send + Sync {
+        /// Picks the member a species is represented by while the next generation is sorted, from its members ranked
+        /// from most to least fit.
+        fn representative<'a>(&self, members: &[&'a G]) -> &'a G;
+
+        /// Assigns every genome a species. Indices below `representatives.len()` are the existing species in order,
+        /// and every higher index founds a new species.
+        fn speciate(&self, genomes: &[G], representatives: &[&G], comp_thresh: f32, config: &G::Config) -> Vec<usize>;
     }
 
     pub trait ConnGene<G: Genome>: Debug + Eq + Send {
@@ -60,9 +153,11 @@ pub mod traits {
         fn innov(&self) -> u32;
     }
 
-    pub trait NodeGene: Clone + Debug + Send { }
+    pub trait NodeGene: Clone + Debug + Send {
+        fn id(&self) -> u32;
+    }
 
-    pub trait Activation: Default {
+    pub trait Activation: Copy + Default {
         fn activate(self, x: f32) -> f32;
     }
 }
\ No newline at end of file
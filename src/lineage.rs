@@ -0,0 +1,157 @@
+use std::{collections::{BTreeMap, BTreeSet}, fmt::{self, Write}};
+
+/// A structural or weight mutation applied to a genome. Connection mutations hold the innovation number of the
+/// connection they touched, and node mutations the id of the node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mutation {
+    AddConn(u32),
+    AddNode(u32),
+    Weight(u32),
+    DelConn(u32),
+    DelNode(u32),
+    ToggleConn(u32),
+}
+
+impl Mutation {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::AddConn(_) => "add_conn",
+            Self::AddNode(_) => "add_node",
+            Self::Weight(_) => "weight",
+            Self::DelConn(_) => "del_conn",
+            Self::DelNode(_) => "del_node",
+            Self::ToggleConn(_) => "toggle_conn",
+        }
+    }
+
+    fn target(&self) -> u32 {
+        match *self {
+            Self::AddConn(target) | Self::AddNode(target) | Self::Weight(target) |
+                Self::DelConn(target) | Self::DelNode(target) | Self::ToggleConn(target) => target,
+        }
+    }
+}
+
+impl fmt::Display for Mutation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::AddNode(node) | Self::DelNode(node) => write!(f, "{} {node}", self.kind()),
+            _ => write!(f, "{} #{}", self.kind(), self.target()),
+        }
+    }
+}
+
+/// Where a genome came from: its unique id, the ids of the genomes it was bred from, the generation it was born in and
+/// the mutations it received after being cloned or crossed over from them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lineage {
+    pub id: u32,
+    pub parents: Vec<u32>,
+    pub generation: usize,
+    pub mutations: Vec<Mutation>,
+}
+
+impl Lineage {
+    pub fn new(id: u32, parents: Vec<u32>, generation: usize) -> Self {
+        Self { id, parents, generation, mutations: Vec::new() }
+    }
+}
+
+/// The lineage of the genomes of a population and their ancestors, keyed by genome id.
+#[derive(Clone, Debug, Default)]
+pub struct Ancestry {
+    lineages: BTreeMap<u32, Lineage>,
+}
+
+impl Ancestry {
+    pub fn get(&self, id: u32) -> Option<&Lineage> {
+        self.lineages.get(&id)
+    }
+
+    /// Every recorded lineage, ordered by genome id.
+    pub fn iter(&self) -> impl Iterator<Item = &Lineage> {
+        self.lineages.values()
+    }
+
+    /// The genome with id `id` together with every recorded ancestor of it.
+    pub fn ancestors(&self, id: u32) -> Self {
+        let mut ancestors = Self::default();
+        let mut stack = vec![id];
+
+        while let Some(id) = stack.pop() {
+            if let Some(lineage) = self.lineages.get(&id).filter(|_| !ancestors.lineages.contains_key(&id)) {
+                stack.extend(lineage.parents.iter().copied());
+                ancestors.record(lineage.clone());
+            }
+        }
+
+        ancestors
+    }
+
+    /// Renders the record as a JSON array with one object per genome, ordered by genome id.
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("[");
+
+        for (i, lineage) in self.lineages.values().enumerate() {
+            let parents = lineage.parents.iter().map(u32::to_string).collect::<Vec<_>>().join(",");
+
+            let mutations = lineage.mutations.iter()
+                .map(|mutation| format!("{{\"kind\":\"{}\",\"target\":{}}}", mutation.kind(), mutation.target()))
+                .collect::<Vec<_>>()
+                .join(",");
+
+            if i > 0 {
+                json.push(',');
+            }
+
+            write!(json, "{{\"id\":{},\"parents\":[{parents}],\"generation\":{},\"mutations\":[{mutations}]}}", lineage.id, lineage.generation).unwrap();
+        }
+
+        json.push(']');
+        json
+    }
+
+    /// Renders the record as a Graphviz DOT graph with an edge from every parent to its child. Each genome is labelled
+    /// with its id, its birth generation and its mutations.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+
+        writeln!(dot, "digraph ancestry {{").unwrap();
+
+        for lineage in self.lineages.values() {
+            let label = [format!("#{}", lineage.id), format!("gen {}", lineage.generation)].into_iter()
+                .chain(lineage.mutations.iter().map(Mutation::to_string))
+                .collect::<Vec<_>>()
+                .join("\\n");
+
+            writeln!(dot, "    g{} [label=\"{label}\", shape=box];", lineage.id).unwrap();
+        }
+
+        for lineage in self.lineages.values() {
+            for parent in lineage.parents.iter().filter(|parent| self.lineages.contains_key(parent)) {
+                writeln!(dot, "    g{parent} -> g{};", lineage.id).unwrap();
+            }
+        }
+
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+
+    pub(crate) fn record(&mut self, lineage: Lineage) {
+        self.lineages.insert(lineage.id, lineage);
+    }
+
+    /// Forgets every lineage that is neither one of `ids` nor an ancestor of one.
+    pub(crate) fn retain_ancestors(&mut self, ids: impl Iterator<Item = u32>) {
+        let mut kept = BTreeSet::new();
+        let mut stack = ids.collect::<Vec<_>>();
+
+        while let Some(id) = stack.pop() {
+            if let Some(lineage) = self.lineages.get(&id).filter(|_| kept.insert(id)) {
+                stack.extend(lineage.parents.iter().copied());
+            }
+        }
+
+        self.lineages.retain(|id, _| kept.contains(id));
+    }
+}
\ No newline at end of file
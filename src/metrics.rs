@@ -0,0 +1,159 @@
+use std::collections::{BTreeMap, BTreeSet};
+use crate::traits::{self, ConnGene, NodeGene};
+
+/// Size and shape statistics of a genome, for dashboards and for tuning parsimony pressure.
+///
+/// Everything but the disabled connection count looks at enabled connections only.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Metrics {
+    pub input_nodes: usize,
+    pub hidden_nodes: usize,
+    pub output_nodes: usize,
+    pub enabled_conns: usize,
+    pub disabled_conns: usize,
+    /// The most connections on any path from an input to an output, not counting recurrent edges.
+    pub depth: usize,
+    /// Incoming connections of the hidden and output nodes.
+    pub fan_in: Degrees,
+    /// Outgoing connections of the input and hidden nodes.
+    pub fan_out: Degrees,
+    /// Connections that close a cycle, which a feed-forward genome never has.
+    pub recurrent_conns: usize,
+    /// The fraction of inputs with a path to at least one output.
+    pub connected_inputs: f32,
+}
+
+/// The spread of connection counts over a set of nodes. Everything is zero when there are no nodes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Degrees {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f32,
+}
+
+impl Metrics {
+    pub fn new<G: traits::Genome>(genome: &G) -> Self {
+        let inputs = genome.input_nodes().map(|node| node.id()).collect::<Vec<_>>();
+        let hidden = genome.hidden_nodes().map(|node| node.id()).collect::<Vec<_>>();
+        let outputs = genome.output_nodes().map(|node| node.id()).collect::<BTreeSet<_>>();
+
+        let edges = genome.conn_genes()
+            .filter(|conn| conn.enabled())
+            .map(|conn| (conn.in_node().id(), conn.out_node().id()))
+            .collect::<Vec<_>>();
+
+        let mut successors = BTreeMap::<u32, Vec<u32>>::new();
+
+        for &(in_node, out_node) in edges.iter() {
+            successors.entry(in_node).or_default().push(out_node);
+        }
+
+        let recurrent = back_edges(inputs.iter().chain(&hidden).chain(&outputs).copied(), &successors);
+
+        let connected = inputs.iter().filter(|&&input| {
+            let mut reached = BTreeSet::new();
+            let mut stack = vec![input];
+
+            while let Some(node) = stack.pop() {
+                if reached.insert(node) {
+                    stack.extend(successors.get(&node).into_iter().flatten());
+                }
+            }
+
+            reached.iter().any(|node| outputs.contains(node))
+        }).count();
+
+        Self {
+            input_nodes: inputs.len(),
+            hidden_nodes: hidden.len(),
+            output_nodes: outputs.len(),
+            enabled_conns: edges.len(),
+            disabled_conns: genome.conn_genes().filter(|conn| !conn.enabled()).count(),
+            depth: depth(&inputs, &outputs, &edges, &recurrent),
+            fan_in: Degrees::new(hidden.iter().chain(&outputs).map(|&node| edges.iter().filter(|(_, out_node)| out_node.eq(&node)).count())),
+            fan_out: Degrees::new(inputs.iter().chain(&hidden).map(|&node| edges.iter().filter(|(in_node, _)| in_node.eq(&node)).count())),
+            recurrent_conns: recurrent.len(),
+            connected_inputs: match inputs.is_empty() {
+                true => 0.0,
+                false => connected as f32 / inputs.len() as f32,
+            },
+        }
+    }
+}
+
+impl Degrees {
+    fn new(degrees: impl Iterator<Item = usize>) -> Self {
+        let degrees = degrees.collect::<Vec<_>>();
+
+        match degrees.is_empty() {
+            true => Self::default(),
+            false => Self {
+                min: *degrees.iter().min().unwrap(),
+                max: *degrees.iter().max().unwrap(),
+                mean: degrees.iter().sum::<usize>() as f32 / degrees.len() as f32,
+            },
+        }
+    }
+}
+
+/// Returns the edges that lead back to a node still on the depth-first search stack, which are exactly the edges
+/// closing a cycle.
+fn back_edges(nodes: impl Iterator<Item = u32>, successors: &BTreeMap<u32, Vec<u32>>) -> BTreeSet<(u32, u32)> {
+    let (mut on_stack, mut done, mut back) = (BTreeSet::new(), BTreeSet::new(), BTreeSet::new());
+
+    for root in nodes {
+        if done.contains(&root) {
+            continue;
+        }
+
+        let mut stack = vec![(root, 0)];
+        on_stack.insert(root);
+
+        while let Some((node, next)) = stack.pop() {
+            match successors.get(&node).and_then(|successors| successors.get(next)) {
+                Some(&successor) => {
+                    stack.push((node, next + 1));
+
+                    if on_stack.contains(&successor) {
+                        back.insert((node, successor));
+                    } else if !done.contains(&successor) {
+                        on_stack.insert(successor);
+                        stack.push((successor, 0));
+                    }
+                }
+                None => {
+                    on_stack.remove(&node);
+                    done.insert(node);
+                }
+            }
+        }
+    }
+
+    back
+}
+
+/// The longest path from an input to an output in edges, over the graph without its recurrent edges.
+fn depth(inputs: &[u32], outputs: &BTreeSet<u32>, edges: &[(u32, u32)], recurrent: &BTreeSet<(u32, u32)>) -> usize {
+    let edges = edges.iter().filter(|edge| !recurrent.contains(edge)).collect::<Vec<_>>();
+    let mut longest = BTreeMap::from_iter(inputs.iter().map(|&input| (input, 0)));
+
+    // Without cycles, relaxing every edge once per edge settles every longest path.
+    for _ in 0..edges.len() {
+        let mut changed = false;
+
+        for &&(in_node, out_node) in edges.iter() {
+            if let Some(&length) = longest.get(&in_node) {
+                if longest.get(&out_node).is_none_or(|&out_length| out_length < length + 1) {
+                    longest.insert(out_node, length + 1);
+                    changed = true;
+                }
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    outputs.iter().filter_map(|output| longest.get(output)).copied().max().unwrap_or(0)
+}
\ No newline at end of file
@@ -0,0 +1,151 @@
+use std::ops::Range;
+use crate::traits;
+use bevy::ecs::component::Component;
+
+/// A flat, lock-free phenotype compiled from a genome.
+///
+/// Node values are laid out as the inputs followed by every other node in topological order. The incoming edges of each
+/// non-input node are stored contiguously, so activation is a single forward pass over plain arrays.
+#[derive(Clone, Component, Debug)]
+pub struct Network<A: traits::Activation> {
+    activation: A,
+    input_len: usize,
+    outputs: Box<[usize]>,
+    edge_offsets: Box<[usize]>,
+    edge_sources: Box<[usize]>,
+    edge_weights: Box<[f32]>,
+    values: Vec<f32>,
+    batch_values: Vec<f32>,
+}
+
+impl<A: traits::Activation> Network<A> {
+    /// Builds a network from the incoming edges of each non-input node, given in topological order as
+    /// `(source, weight)` pairs indexing into the node values.
+    pub(crate) fn new(activation: A, input_len: usize, outputs: Box<[usize]>, nodes: Vec<Vec<(usize, f32)>>) -> Self {
+        let mut edge_offsets = Vec::with_capacity(nodes.len() + 1);
+        let (mut edge_sources, mut edge_weights) = (Vec::new(), Vec::new());
+
+        edge_offsets.push(0);
+
+        for (i, edges) in nodes.iter().enumerate() {
+            for &(source, weight) in edges {
+                assert!(source < input_len + i);
+                edge_sources.push(source);
+                edge_weights.push(weight);
+            }
+
+            edge_offsets.push(edge_sources.len());
+        }
+
+        Self {
+            activation,
+            input_len,
+            outputs,
+            edge_offsets: edge_offsets.into(),
+            edge_sources: edge_sources.into(),
+            edge_weights: edge_weights.into(),
+            values: vec![0.0; input_len + nodes.len()],
+            batch_values: Vec::new(),
+        }
+    }
+
+    pub fn input_len(&self) -> usize {
+        self.input_len
+    }
+
+    pub fn output_len(&self) -> usize {
+        self.outputs.len()
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edge_sources.len()
+    }
+
+    fn edges(&self, node: usize) -> Range<usize> {
+        self.edge_offsets[node]..self.edge_offsets[node + 1]
+    }
+
+    pub fn activate(&mut self, input: &[f32]) -> Vec<f32> {
+        let mut output = vec![0.0; self.output_len()];
+        self.activate_into(input, &mut output);
+        output
+    }
+
+    /// Activates the network without allocating, writing one value per output node into `output`.
+    pub fn activate_into(&mut self, input: &[f32], output: &mut [f32]) {
+        assert_eq!(input.len(), self.input_len);
+        assert_eq!(output.len(), self.output_len());
+
+        for (value, &x) in self.values.iter_mut().zip(input) {
+            *value = self.activation.activate(x);
+        }
+
+        for node in 0..self.values.len() - self.input_len {
+            let edges = self.edges(node);
+
+            let sum = self.edge_sources[edges.clone()].iter()
+                .zip(&self.edge_weights[edges])
+                .map(|(&source, &weight)| self.values[source] * weight)
+                .sum::<f32>();
+
+            self.values[self.input_len + node] = self.activation.activate(sum);
+        }
+
+        for (value, &node) in output.iter_mut().zip(self.outputs.iter()) {
+            *value = self.values[node];
+        }
+    }
+
+    pub fn activate_batch(&mut self, inputs: &[f32]) -> Vec<f32> {
+        let mut outputs = vec![0.0; inputs.len() / self.input_len * self.output_len()];
+        self.activate_batch_into(inputs, &mut outputs);
+        outputs
+    }
+
+    /// Activates the network once per sample. `inputs` holds one row of `input_len` values per sample and `outputs`
+    /// receives one row of `output_len` values per sample.
+    ///
+    /// Values are stored node-major while activating, so every edge is applied to the whole batch as one contiguous
+    /// multiply-add that the compiler can vectorize. The scratch buffer only reallocates when the batch grows.
+    pub fn activate_batch_into(&mut self, inputs: &[f32], outputs: &mut [f32]) {
+        let samples = inputs.len() / self.input_len;
+        assert_eq!(inputs.len(), samples * self.input_len);
+        assert_eq!(outputs.len(), samples * self.output_len());
+
+        self.batch_values.resize(self.values.len() * samples, 0.0);
+
+        for (sample, row) in inputs.chunks_exact(self.input_len).enumerate() {
+            for (input, &x) in row.iter().enumerate() {
+                self.batch_values[input * samples + sample] = self.activation.activate(x);
+            }
+        }
+
+        for node in 0..self.values.len() - self.input_len {
+            let (done, rest) = self.batch_values.split_at_mut((self.input_len + node) * samples);
+            let sums = &mut rest[..samples];
+            sums.fill(0.0);
+
+            let edges = self.edge_offsets[node]..self.edge_offsets[node + 1];
+
+            for (&source, &weight) in self.edge_sources[edges.clone()].iter().zip(&self.edge_weights[edges]) {
+                for (sum, &value) in sums.iter_mut().zip(&done[source * samples..(source + 1) * samples]) {
+                    *sum += value * weight;
+                }
+            }
+
+            for sum in sums.iter_mut() {
+                *sum = self.activation.activate(*sum);
+            }
+        }
+
+        for (output, &node) in self.outputs.iter().enumerate() {
+            for (sample, &value) in self.batch_values[node * samples..(node + 1) * samples].iter().enumerate() {
+                outputs[sample * self.outputs.len() + output] = value;
+            }
+        }
+    }
+}
\ No newline at end of file
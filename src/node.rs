@@ -26,53 +26,57 @@ impl<G: traits::Genome> Ord for NodeKind<G> {
 
 impl<G: traits::Genome> PartialOrd for NodeKind<G> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.cmp(&other).into()
+        Some(self.cmp(other))
     }
 }
 
 #[derive(Clone, Debug)]
-pub struct NodeGene<G: traits::Genome>(Arc<Mutex<NodeKind<G>>>);
+pub struct NodeGene<G: traits::Genome> {
+    id: u32,
+    kind: Arc<Mutex<NodeKind<G>>>,
+}
 
 impl<G: traits::Genome> NodeGene<G> {
-    pub fn new_input() -> Self {
-        Self(Arc::new(Mutex::new(NodeKind::Input { forward: Default::default() })))
+    pub fn new_input(id: u32) -> Self {
+        Self { id, kind: Arc::new(Mutex::new(NodeKind::Input { forward: Default::default() })) }
     }
     
-    pub fn new_hidden() -> Self {
-        Self(Arc::new(Mutex::new(NodeKind::Hidden { forward: Default::default(), backward: Default::default() })))
+    pub fn new_hidden(id: u32) -> Self {
+        Self { id, kind: Arc::new(Mutex::new(NodeKind::Hidden { forward: Default::default(), backward: Default::default() })) }
     }
 
-    pub fn new_output() -> Self {
-        Self(Arc::new(Mutex::new(NodeKind::Output { backward: Default::default() })))
+    pub fn new_output(id: u32) -> Self {
+        Self { id, kind: Arc::new(Mutex::new(NodeKind::Output { backward: Default::default() })) }
     }
 
-    fn kind(&self) -> Arc<Mutex<NodeKind<G>>> {
-        self.0.clone()
+    /// Whether both handles point at the same gene rather than merely sharing an id.
+    pub(crate) fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.kind, &other.kind)
     }
 
     pub fn forward<T>(&self, f: impl Fn(&BTreeSet<G::ConnGene>) -> T) -> T {
-        match self.0.lock().unwrap().deref() {
+        match self.kind.lock().unwrap().deref() {
             NodeKind::Input { forward } | NodeKind::Hidden { forward, .. } => f(forward),
             NodeKind::Output { .. } => f(&Default::default()),
         }
     }
 
     pub fn backward<T>(&self, f: impl Fn(&BTreeSet<G::ConnGene>) -> T) -> T {
-        match self.0.lock().unwrap().deref() {
+        match self.kind.lock().unwrap().deref() {
             NodeKind::Input { .. } => f(&mut Default::default()),
             NodeKind::Hidden { backward, .. } | NodeKind::Output { backward } => f(backward),
         }
     }
 
     pub fn forward_mut<T>(&self, mut f: impl FnMut(&mut BTreeSet<G::ConnGene>) -> T) -> T {
-        match self.0.lock().unwrap().deref_mut() {
+        match self.kind.lock().unwrap().deref_mut() {
             NodeKind::Input { forward } | NodeKind::Hidden { forward, .. } => f(forward),
             NodeKind::Output { .. } => f(&mut Default::default()),
         }
     }
 
     pub fn backward_mut<T>(&self, mut f: impl FnMut(&mut BTreeSet<G::ConnGene>) -> T) -> T {
-        match self.0.lock().unwrap().deref_mut() {
+        match self.kind.lock().unwrap().deref_mut() {
             NodeKind::Input { .. } => f(&mut Default::default()),
             NodeKind::Hidden { backward, .. } | NodeKind::Output { backward } => f(backward),
         }
@@ -81,30 +85,28 @@ impl<G: traits::Genome> NodeGene<G> {
 
 impl<G: traits::Genome> Eq for NodeGene<G> { }
 
+// Nodes are keyed by their id alone. The id never changes, so ordering stays valid while a node sits inside an ordered
+// collection and its adjacency sets are being mutated.
 impl<G: traits::Genome> Ord for NodeGene<G> {
     fn cmp(&self, other: &Self) -> Ordering {
-        if Arc::ptr_eq(&self.kind(), &other.kind()) {
-            return Ordering::Equal;
-        }
-
-        let temp = self.backward(|backward| backward.len())
-            .cmp(&other.backward(|backward| backward.len()))
-            .reverse();
-
-        self.kind().lock().unwrap().cmp(&other.kind().lock().unwrap()).clone().then(temp)
+        self.id.cmp(&other.id)
     }
 }
 
 impl<G: traits::Genome> PartialEq for NodeGene<G> {
     fn eq(&self, other: &Self) -> bool {
-        Arc::ptr_eq(&self.kind(), &other.kind())
+        self.id.eq(&other.id)
     }
 }
 
 impl<G: traits::Genome> PartialOrd for NodeGene<G> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.cmp(&other).into()
+        Some(self.cmp(other))
     }
 }
 
-impl<G: traits::Genome> traits::NodeGene for NodeGene<G> { }
\ No newline at end of file
+impl<G: traits::Genome> traits::NodeGene for NodeGene<G> {
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
\ No newline at end of file
@@ -0,0 +1,116 @@
+use std::num::NonZeroUsize;
+use bevy::ecs::system::Resource;
+use rand::Rng;
+
+/// Decides which behaviors are kept in the novelty archive once they have been scored.
+#[derive(Clone, Copy, Debug)]
+pub enum ArchiveAdmission {
+    /// Admits every behavior whose novelty exceeds the threshold.
+    Threshold(f32),
+    /// Admits each behavior with a fixed probability in `[0, 1]`, regardless of its novelty.
+    Random(f64),
+}
+
+/// Scores behavior descriptors by their mean distance to the `k` nearest behaviors among the rest of the current
+/// population and an archive of past behaviors.
+#[derive(Clone, Debug, Resource)]
+pub struct Novelty {
+    k: usize,
+    admission: ArchiveAdmission,
+    archive: Vec<Vec<f32>>,
+}
+
+impl Novelty {
+    pub fn new(k: NonZeroUsize, admission: ArchiveAdmission) -> Self {
+        if let ArchiveAdmission::Random(probability) = admission {
+            assert!((0.0..=1.0).contains(&probability), "archive admission probability must be in [0, 1]");
+        }
+
+        Self { k: k.into(), admission, archive: Vec::new() }
+    }
+
+    pub fn archive(&self) -> &[Vec<f32>] {
+        &self.archive
+    }
+
+    pub(crate) fn evaluate(&mut self, behaviors: &[Vec<f32>]) -> Vec<f32> {
+        let scores = behaviors.iter().enumerate().map(|(i, behavior)| {
+            let mut distances = behaviors.iter().enumerate()
+                .filter(|(j, _)| i.ne(j))
+                .map(|(_, other)| other)
+                .chain(&self.archive)
+                .map(|other| distance(behavior, other))
+                .collect::<Vec<_>>();
+
+            let k = self.k.min(distances.len());
+
+            if k == 0 {
+                return 0.0;
+            }
+
+            distances.select_nth_unstable_by(k - 1, f32::total_cmp);
+            distances[..k].iter().sum::<f32>() / k as f32
+        }).collect::<Vec<_>>();
+
+        let mut rng = rand::thread_rng();
+
+        for (behavior, &score) in behaviors.iter().zip(&scores) {
+            let admit = match self.admission {
+                ArchiveAdmission::Threshold(threshold) => score > threshold,
+                ArchiveAdmission::Random(probability) => rng.gen_bool(probability),
+            };
+
+            if admit {
+                self.archive.push(behavior.clone());
+            }
+        }
+
+        scores
+    }
+}
+
+fn distance(lhs: &[f32], rhs: &[f32]) -> f32 {
+    assert_eq!(lhs.len(), rhs.len());
+    lhs.iter().zip(rhs).map(|(a, b)| (a - b).powi(2)).sum::<f32>().sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+    use super::{ArchiveAdmission, Novelty};
+
+    fn behaviors(values: &[f32]) -> Vec<Vec<f32>> {
+        values.iter().map(|&value| vec![value]).collect()
+    }
+
+    #[test]
+    fn scores_are_mean_distances_to_the_k_nearest() {
+        let mut novelty = Novelty::new(NonZeroUsize::new(2).unwrap(), ArchiveAdmission::Threshold(f32::INFINITY));
+
+        assert_eq!(novelty.evaluate(&behaviors(&[0.0, 1.0, 3.0, 7.0])), vec![2.0, 1.5, 2.5, 5.0]);
+        assert!(novelty.archive().is_empty());
+    }
+
+    #[test]
+    fn behaviors_above_the_threshold_join_the_archive_and_count_later() {
+        let mut novelty = Novelty::new(NonZeroUsize::new(2).unwrap(), ArchiveAdmission::Threshold(2.0));
+
+        novelty.evaluate(&behaviors(&[0.0, 1.0, 3.0, 7.0]));
+        assert_eq!(novelty.archive(), behaviors(&[3.0, 7.0]));
+
+        // A lone behavior is only scored against the archive.
+        assert_eq!(novelty.evaluate(&behaviors(&[4.0])), vec![2.0]);
+    }
+
+    #[test]
+    fn random_admission_follows_its_probability() {
+        let mut never = Novelty::new(NonZeroUsize::new(1).unwrap(), ArchiveAdmission::Random(0.0));
+        let mut always = Novelty::new(NonZeroUsize::new(1).unwrap(), ArchiveAdmission::Random(1.0));
+
+        never.evaluate(&behaviors(&[0.0, 1.0, 2.0]));
+        always.evaluate(&behaviors(&[0.0, 1.0, 2.0]));
+
+        assert!(never.archive().is_empty());
+        assert_eq!(always.archive(), behaviors(&[0.0, 1.0, 2.0]));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,91 @@
+/// Returns `true` when `lhs` is at least as good as `rhs` in every objective and better in at least one. Every
+/// objective is maximized.
+pub fn dominates(lhs: &[f32], rhs: &[f32]) -> bool {
+    assert_eq!(lhs.len(), rhs.len());
+    lhs.iter().zip(rhs).all(|(a, b)| a >= b) && lhs.iter().zip(rhs).any(|(a, b)| a > b)
+}
+
+/// Sorts objective vectors into non-dominated fronts, best front first. Each front holds indices into `objectives`.
+pub fn pareto_fronts(objectives: &[Vec<f32>]) -> Vec<Vec<usize>> {
+    let mut dominated = vec![Vec::new(); objectives.len()];
+    let mut domination_counts = vec![0; objectives.len()];
+
+    for (i, lhs) in objectives.iter().enumerate() {
+        for (j, rhs) in objectives.iter().enumerate() {
+            if dominates(lhs, rhs) {
+                dominated[i].push(j);
+            } else if dominates(rhs, lhs) {
+                domination_counts[i] += 1;
+            }
+        }
+    }
+
+    let mut fronts = Vec::new();
+    let mut front = (0..objectives.len()).filter(|&i| domination_counts[i] == 0).collect::<Vec<_>>();
+
+    while !front.is_empty() {
+        let mut next = Vec::new();
+
+        for &i in front.iter() {
+            for &j in dominated[i].iter() {
+                domination_counts[j] -= 1;
+
+                if domination_counts[j] == 0 {
+                    next.push(j);
+                }
+            }
+        }
+
+        fronts.push(front);
+        front = next;
+    }
+
+    fronts
+}
+
+/// Returns the crowding distance of every member of `front`, in the same order. Members at either end of an
+/// objective's range get an infinite distance.
+pub fn crowding_distances(objectives: &[Vec<f32>], front: &[usize]) -> Vec<f32> {
+    let mut distances = vec![0.0; front.len()];
+    let mut order = (0..front.len()).collect::<Vec<_>>();
+
+    let dimensions = front.first().map_or(0, |&i| objectives[i].len());
+
+    for values in (0..dimensions).map(|objective| front.iter().map(|&i| objectives[i][objective]).collect::<Vec<_>>()) {
+        order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+
+        let (first, last) = (*order.first().unwrap(), *order.last().unwrap());
+        let range = values[last] - values[first];
+
+        distances[first] = f32::INFINITY;
+        distances[last] = f32::INFINITY;
+
+        if range > 0.0 {
+            for window in order.windows(3) {
+                distances[window[1]] += (values[window[2]] - values[window[0]]) / range;
+            }
+        }
+    }
+
+    distances
+}
+
+/// Collapses objective vectors into scalar fitnesses that preserve the NSGA-II ordering: every genome in a better
+/// front scores higher than every genome in a worse one, and within a front a larger crowding distance scores higher.
+pub(crate) fn pareto_fitness(objectives: &[Vec<f32>]) -> Vec<f32> {
+    let fronts = pareto_fronts(objectives);
+    let mut fitness = vec![0.0; objectives.len()];
+
+    for (rank, front) in fronts.iter().enumerate() {
+        for (&i, distance) in front.iter().zip(crowding_distances(objectives, front)) {
+            let crowding = match distance.is_finite() {
+                true => 0.5 * distance / (distance + 1.0),
+                false => 0.5,
+            };
+
+            fitness[i] = (fronts.len() - rank) as f32 + crowding;
+        }
+    }
+
+    fitness
+}
\ No newline at end of file
@@ -1,36 +1,549 @@
-use std::iter;
-use crate::traits::{self, Config};
+use std::{cmp, collections::BTreeMap, error, fmt, iter};
+use crate::{Ancestry, InnovConflict, Lineage, Mutation, Novelty, pareto, traits::{self, Config, ConnGene, NodeGene}};
 use bevy::ecs::system::Resource;
+use rand::{seq::IteratorRandom, Rng};
+
+// The chances that an offspring is produced by crossover rather than by cloning a single parent, and that each kind of
+// mutation is applied to it afterwards.
+const CROSSOVER_RATE: f64 = 0.75;
+const WEIGHT_MUTATION_RATE: f64 = 0.8;
+const ADD_CONN_MUTATION_RATE: f64 = 0.05;
+const ADD_NODE_MUTATION_RATE: f64 = 0.03;
+const DEL_CONN_MUTATION_RATE: f64 = 0.02;
+const DEL_NODE_MUTATION_RATE: f64 = 0.01;
+const TOGGLE_CONN_MUTATION_RATE: f64 = 0.01;
+
+// How far each weight of a seeded genome's copies may be nudged.
+const SEED_JITTER_POWER: f32 = 0.1;
 
 #[derive(Resource)]
 pub struct Population<G: traits::Genome> {
     config: G::Config,
     species: Vec<Species<G>>,
+    comp_thresh: f32,
+    generation: usize,
+    ancestry: Ancestry,
 }
 
 impl<G: traits::Genome> Population<G> {
     pub fn new(config: G::Config) -> Self {
-        Self {
+        let mut population = Self {
             config: config.clone(),
             species: iter::once(Species {
                 representative: G::minimal(&config),
                 shared_fitness: Default::default(),
+                best_fitness: Default::default(),
+                stagnation: 0,
                 members: iter::repeat_with(|| G::minimal(&config)).take(config.pop_size()).collect()
             }).collect(),
+            comp_thresh: config.comp_thresh(),
+            generation: 0,
+            ancestry: Ancestry::default(),
+        };
+
+        population.record_members();
+        population
+    }
+
+    /// Seeds the population from existing genomes, such as the champions of an earlier run. Every seed is kept as is,
+    /// and the rest of the population is filled with copies of the seeds, taken in turn, with every weight nudged by a
+    /// small amount.
+    ///
+    /// Fails without registering any seed with the config when there are no seeds, more seeds than the population
+    /// size, a seed with the wrong number of inputs or outputs, or innovation numbers that conflict with the config
+    /// history or between seeds.
+    pub fn from_genomes(config: G::Config, seeds: Vec<G>) -> Result<Self, SeedError> {
+        if seeds.is_empty() {
+            return Err(SeedError::NoSeeds);
         }
+
+        if seeds.len() > config.pop_size() {
+            return Err(SeedError::TooManySeeds { seeds: seeds.len(), pop_size: config.pop_size() });
+        }
+
+        for (seed, genome) in seeds.iter().enumerate() {
+            let (inputs, outputs) = (genome.input_nodes().count(), genome.output_nodes().count());
+
+            if inputs != config.input_len() || outputs != config.output_len() {
+                return Err(SeedError::WrongShape { seed, inputs, outputs });
+            }
+        }
+
+        config.register(&seeds).map_err(SeedError::InnovConflicts)?;
+
+        let jittered = seeds.iter().cycle().map(|seed| {
+            let mut genome = seed.clone();
+            let mut lineage = Lineage::new(config.genome_id(), vec![seed.lineage().id], 0);
+
+            genome.jitter_weights(SEED_JITTER_POWER, &config);
+            lineage.mutations.extend(genome.conn_genes().map(|conn| Mutation::Weight(conn.innov())));
+
+            genome.set_lineage(lineage, &config);
+            genome
+        });
+
+        let members = seeds.iter().cloned().chain(jittered.take(config.pop_size() - seeds.len())).collect();
+
+        let mut population = Self {
+            config: config.clone(),
+            species: Vec::new(),
+            comp_thresh: config.comp_thresh(),
+            generation: 0,
+            ancestry: Ancestry::default(),
+        };
+
+        population.speciate(members);
+        population.record_members();
+        Ok(population)
+    }
+
+    pub fn config(&self) -> &G::Config {
+        &self.config
+    }
+
+    pub fn species(&self) -> &[Species<G>] {
+        &self.species
     }
 
+    /// The compatibility threshold used for the next speciation, which only differs from the config's when it targets
+    /// a species count.
+    pub fn comp_thresh(&self) -> f32 {
+        self.comp_thresh
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn members(&self) -> impl Iterator<Item = &G> {
+        self.species.iter().flat_map(|species| species.members.iter())
+    }
+
+    /// The lineage of every current member and of every earlier genome that is an ancestor of one.
+    pub fn ancestry(&self) -> &Ancestry {
+        &self.ancestry
+    }
+
+    /// Scores every genome with `fitness_fn` and replaces the population with the next generation.
     pub fn run(&mut self, fitness_fn: impl Fn(&G, &G::Config) -> f32) {
         for genome in self.species.iter_mut().flat_map(|species| species.members.iter_mut()) {
-            genome.set_fitness(fitness_fn(&genome, &self.config), &self.config);
+            genome.set_fitness(fitness_fn(genome, &self.config), &self.config);
+        }
+
+        self.next_generation();
+    }
+
+    /// Scores every genome by the novelty of the behavior descriptor returned by `behavior_fn` and replaces the
+    /// population with the next generation.
+    pub fn run_novelty(&mut self, novelty: &mut Novelty, behavior_fn: impl Fn(&G, &G::Config) -> Vec<f32>) {
+        let behaviors = self.members().map(|genome| behavior_fn(genome, &self.config)).collect::<Vec<_>>();
+        let scores = novelty.evaluate(&behaviors);
+
+        for (genome, score) in self.species.iter_mut().flat_map(|species| species.members.iter_mut()).zip(scores) {
+            genome.set_fitness(score, &self.config);
+        }
+
+        self.next_generation();
+    }
+
+    /// Scores every genome on several objectives at once and replaces the population with the next generation.
+    ///
+    /// Genomes are ranked into non-dominated fronts with crowding distance breaking ties inside a front, and that rank
+    /// becomes the fitness that species share and reproduce by. Every objective is maximized.
+    pub fn run_multi_objective(&mut self, objectives_fn: impl Fn(&G, &G::Config) -> Vec<f32>) {
+        for genome in self.species.iter_mut().flat_map(|species| species.members.iter_mut()) {
+            genome.set_objectives(objectives_fn(genome, &self.config), &self.config);
+        }
+
+        let objectives = self.members().map(|genome| genome.objectives().to_vec()).collect::<Vec<_>>();
+
+        for (genome, fitness) in self.species.iter_mut().flat_map(|species| species.members.iter_mut()).zip(pareto::pareto_fitness(&objectives)) {
+            genome.set_fitness(fitness, &self.config);
         }
 
-        todo!()
+        self.next_generation();
+    }
+
+    fn next_generation(&mut self) {
+        self.apply_parsimony();
+
+        for species in self.species.iter_mut() {
+            species.shared_fitness = Some(species.members.iter().map(|genome| genome.fitness().unwrap()).sum::<f32>() / species.members.len() as f32);
+            species.track_stagnation();
+        }
+
+        self.cull_stagnant();
+
+        let ranked = self.species.iter().map(Species::ranked).collect::<Vec<_>>();
+
+        let parents = ranked.iter().map(|ranked| {
+            let survivors = (ranked.len() as f32 * self.config.survival_threshold()).ceil() as usize;
+            &ranked[..survivors.clamp(1, ranked.len())]
+        }).collect::<Vec<_>>();
+
+        // Each species starts its share of the next generation with its elites and breeds the rest.
+        let offspring = self.offspring_counts().into_iter().enumerate().flat_map(|(i, count)| {
+            let elites = self.config.elitism().min(count).min(ranked[i].len());
+
+            ranked[i][..elites].iter().map(|&genome| genome.clone())
+                .chain(iter::repeat_with(|| self.offspring(i, &parents)).take(count - elites))
+                .collect::<Vec<_>>()
+        }).collect::<Vec<_>>();
+
+        self.speciate(offspring);
+        self.adjust_comp_thresh();
+        self.generation += 1;
+        self.record_members();
+    }
+
+    /// Records the lineage of every member and forgets every genome that is no longer an ancestor of one, so that the
+    /// record doesn't grow with every generation.
+    fn record_members(&mut self) {
+        let members = self.members().map(|genome| genome.lineage().clone()).collect::<Vec<_>>();
+        let ids = members.iter().map(|lineage| lineage.id).collect::<Vec<_>>();
+
+        for lineage in members {
+            self.ancestry.record(lineage);
+        }
+
+        self.ancestry.retain_ancestors(ids.into_iter());
+    }
+
+    fn apply_parsimony(&mut self) {
+        let (node_penalty, conn_penalty) = (self.config.node_penalty(), self.config.conn_penalty());
+
+        if node_penalty == 0.0 && conn_penalty == 0.0 {
+            return;
+        }
+
+        for genome in self.species.iter_mut().flat_map(|species| species.members.iter_mut()) {
+            let penalty = node_penalty * genome.hidden_nodes().count() as f32
+                + conn_penalty * genome.conn_genes().filter(|conn| conn.enabled()).count() as f32;
+
+            genome.set_fitness(genome.fitness().unwrap() - penalty, &self.config);
+        }
+    }
+
+    /// Breeds one offspring for species `species`, given the parents of every species. Parents are picked with the
+    /// configured selection, and crossover takes its second parent from another species at the interspecies mating
+    /// rate.
+    fn offspring(&self, species: usize, parents: &[&[&G]]) -> G {
+        let mut rng = rand::thread_rng();
+        let selection = self.config.selection();
+        let parent = selection.select(parents[species], &mut rng);
+
+        let mates = match parents.len() > 1 && rng.gen_bool(self.config.interspecies_mating_rate()) {
+            true => (0..parents.len()).filter(|&i| i != species).choose(&mut rng).unwrap(),
+            false => species,
+        };
+
+        // Crossover already gives the child a fresh id and both parents, so only a clone needs a new lineage.
+        let (mut child, mut lineage) = match (mates != species || parents[species].len() > 1) && rng.gen_bool(CROSSOVER_RATE) {
+            true => {
+                let child = parent.crossover(selection.select(parents[mates], &mut rng), &self.config);
+                let lineage = child.lineage().clone();
+                (child, lineage)
+            }
+            false => (parent.clone(), Lineage::new(self.config.genome_id(), vec![parent.lineage().id], 0)),
+        };
+
+        lineage.generation = self.generation + 1;
+
+        if rng.gen_bool(WEIGHT_MUTATION_RATE) {
+            lineage.mutations.extend(child.mut_conn_weight(&self.config).map(|conn| Mutation::Weight(conn.innov())));
+        }
+
+        if rng.gen_bool(ADD_CONN_MUTATION_RATE) {
+            lineage.mutations.extend(child.add_conn_mut(&self.config).map(|conn| Mutation::AddConn(conn.innov())));
+        }
+
+        if rng.gen_bool(ADD_NODE_MUTATION_RATE) {
+            lineage.mutations.extend(child.add_node_mut(&self.config).map(|node| Mutation::AddNode(node.id())));
+        }
+
+        if rng.gen_bool(DEL_CONN_MUTATION_RATE) {
+            lineage.mutations.extend(child.del_conn_mut(&self.config).map(|conn| Mutation::DelConn(conn.innov())));
+        }
+
+        if rng.gen_bool(DEL_NODE_MUTATION_RATE) {
+            lineage.mutations.extend(child.del_node_mut(&self.config).map(|node| Mutation::DelNode(node.id())));
+        }
+
+        if rng.gen_bool(TOGGLE_CONN_MUTATION_RATE) {
+            lineage.mutations.extend(child.toggle_conn_mut(&self.config).map(|conn| Mutation::ToggleConn(conn.innov())));
+        }
+
+        child.set_lineage(lineage, &self.config);
+        child
+    }
+
+    fn adjust_comp_thresh(&mut self) {
+        let Some(target) = self.config.species_target() else {
+            return;
+        };
+
+        match self.species.len().cmp(&target.count) {
+            cmp::Ordering::Less => self.comp_thresh -= target.step,
+            cmp::Ordering::Greater => self.comp_thresh += target.step,
+            cmp::Ordering::Equal => (),
+        }
+
+        self.comp_thresh = self.comp_thresh.clamp(target.min_thresh, target.max_thresh);
+    }
+
+    /// Removes the species that have stagnated for `max_stagnation` generations, sparing the `species_elitism` species
+    /// with the highest best fitness. The best species is always spared so that the population can't die out.
+    fn cull_stagnant(&mut self) {
+        let Some(max_stagnation) = self.config.max_stagnation() else {
+            return;
+        };
+
+        self.species.sort_by(|a, b| b.best_fitness.unwrap().total_cmp(&a.best_fitness.unwrap()));
+
+        let protected = self.config.species_elitism().max(1);
+        let mut rank = 0;
+
+        self.species.retain(|species| {
+            rank += 1;
+            rank <= protected || species.stagnation < max_stagnation
+        });
+    }
+
+    /// Splits `pop_size` offspring between the species. Every species first gets `min_species_size` of them, or an even
+    /// share when there are too many species for that, and the rest goes out in proportion to how far each species'
+    /// shared fitness lies above the lowest one.
+    fn offspring_counts(&self) -> Vec<usize> {
+        let pop_size = self.config.pop_size();
+        let floor = self.config.min_species_size().min(pop_size / self.species.len());
+        let spare = pop_size - floor * self.species.len();
+
+        let min = self.species.iter().map(|species| species.shared_fitness.unwrap()).fold(f32::INFINITY, f32::min);
+        let shares = self.species.iter().map(|species| species.shared_fitness.unwrap() - min).collect::<Vec<_>>();
+        let total = shares.iter().sum::<f32>();
+
+        let quotas = shares.iter().map(|share| match total > 0.0 {
+            true => share / total * spare as f32,
+            false => spare as f32 / shares.len() as f32,
+        }).collect::<Vec<_>>();
+
+        let mut counts = quotas.iter().map(|quota| floor + quota.floor() as usize).collect::<Vec<_>>();
+
+        // Hand out the slots lost to rounding down, largest remainder first.
+        let mut remainders = (0..quotas.len()).collect::<Vec<_>>();
+        remainders.sort_by(|&a, &b| (quotas[b] - quotas[b].floor()).total_cmp(&(quotas[a] - quotas[a].floor())));
+
+        for i in remainders.into_iter().cycle().take(pop_size - counts.iter().sum::<usize>()) {
+            counts[i] += 1;
+        }
+
+        counts
+    }
+
+    /// Sorts `offspring` into species with the configured speciation, founding new species as it asks for them.
+    fn speciate(&mut self, offspring: Vec<G>) {
+        let speciation = self.config.speciation();
+
+        for species in self.species.iter_mut() {
+            species.representative = speciation.representative(&species.ranked()).clone();
+            species.members.clear();
+        }
+
+        let representatives = self.species.iter().map(|species| &species.representative).collect::<Vec<_>>();
+        let assignments = speciation.speciate(&offspring, &representatives, self.comp_thresh, &self.config);
+        let (existing, mut founded) = (self.species.len(), BTreeMap::new());
+
+        for (genome, species) in offspring.into_iter().zip(assignments) {
+            let species = match species < existing {
+                true => species,
+                false => *founded.entry(species).or_insert_with(|| {
+                    self.species.push(Species {
+                        representative: genome.clone(),
+                        shared_fitness: Default::default(),
+                        best_fitness: Default::default(),
+                        stagnation: 0,
+                        members: Vec::new(),
+                    });
+
+                    self.species.len() - 1
+                }),
+            };
+
+            self.species[species].members.push(genome);
+        }
+
+        self.species.retain(|species| !species.members.is_empty());
     }
 }
 
 pub struct Species<G: traits::Genome> {
     representative: G,
     shared_fitness: Option<f32>,
+    best_fitness: Option<f32>,
+    stagnation: usize,
     members: Vec<G>
+}
+
+impl<G: traits::Genome> Species<G> {
+    pub fn representative(&self) -> &G {
+        &self.representative
+    }
+
+    pub fn shared_fitness(&self) -> Option<f32> {
+        self.shared_fitness
+    }
+
+    /// The highest fitness any member of the species has ever reached.
+    pub fn best_fitness(&self) -> Option<f32> {
+        self.best_fitness
+    }
+
+    /// The number of generations since the species last improved its best fitness.
+    pub fn stagnation(&self) -> usize {
+        self.stagnation
+    }
+
+    pub fn members(&self) -> &[G] {
+        &self.members
+    }
+
+    fn track_stagnation(&mut self) {
+        let best = self.members.iter().map(|genome| genome.fitness().unwrap()).fold(f32::NEG_INFINITY, f32::max);
+
+        match self.best_fitness.is_some_and(|best_fitness| best <= best_fitness) {
+            true => self.stagnation += 1,
+            false => {
+                self.best_fitness = Some(best);
+                self.stagnation = 0;
+            }
+        }
+    }
+
+    fn ranked(&self) -> Vec<&G> {
+        let mut ranked = self.members.iter().collect::<Vec<_>>();
+        ranked.sort_by(|a, b| b.fitness().unwrap().total_cmp(&a.fitness().unwrap()));
+        ranked
+    }
+}
+
+/// Why [`Population::from_genomes`] could not seed a population.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SeedError {
+    NoSeeds,
+    TooManySeeds { seeds: usize, pop_size: usize },
+    /// The seed at this index has a different number of inputs or outputs than the config.
+    WrongShape { seed: usize, inputs: usize, outputs: usize },
+    InnovConflicts(Vec<InnovConflict>),
+}
+
+impl fmt::Display for SeedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoSeeds => write!(f, "no seed genomes were given"),
+            Self::TooManySeeds { seeds, pop_size } => write!(f, "{seeds} seed genomes do not fit in a population of {pop_size}"),
+            Self::WrongShape { seed, inputs, outputs } => write!(f, "seed genome {seed} has {inputs} inputs and {outputs} outputs"),
+            Self::InnovConflicts(conflicts) => write!(f, "{} connections conflict with the innovation history", conflicts.len()),
+        }
+    }
+}
+
+impl error::Error for SeedError { }
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+    use crate::{Ancestry, Config, FeedForwardGenome, InitialTopology, SpeciesTarget, traits::{Config as _, Genome}};
+    use super::{Population, Species};
+
+    fn config(pop_size: usize) -> Config<FeedForwardGenome> {
+        Config::new(NonZeroUsize::new(2).unwrap(), NonZeroUsize::new(1).unwrap(), NonZeroUsize::new(pop_size).unwrap(), 3.0, 1.0, 1.0, 0.4)
+    }
+
+    /// Builds a population with one single-member species per shared fitness, each of which is also its best fitness.
+    fn population(config: Config<FeedForwardGenome>, shared_fitness: &[f32]) -> Population<FeedForwardGenome> {
+        Population {
+            species: shared_fitness.iter().map(|&fitness| Species {
+                representative: FeedForwardGenome::minimal(&config),
+                shared_fitness: Some(fitness),
+                best_fitness: Some(fitness),
+                stagnation: 0,
+                members: vec![FeedForwardGenome::minimal(&config)],
+            }).collect(),
+            comp_thresh: config.comp_thresh(),
+            generation: 0,
+            ancestry: Ancestry::default(),
+            config,
+        }
+    }
+
+    #[test]
+    fn least_fit_species_still_gets_offspring() {
+        let counts = population(config(20), &[1.0, 5.0, 9.0]).offspring_counts();
+
+        assert_eq!(counts.iter().sum::<usize>(), 20);
+        assert!(counts.iter().all(|&count| count >= 2), "{counts:?}");
+        assert!(counts[0] < counts[1] && counts[1] < counts[2], "{counts:?}");
+    }
+
+    #[test]
+    fn crowded_population_still_gives_every_species_offspring() {
+        let shared_fitness = (0..15).map(|i| i as f32).collect::<Vec<_>>();
+        let counts = population(config(20), &shared_fitness).offspring_counts();
+
+        assert_eq!(counts.iter().sum::<usize>(), 20);
+        assert!(counts.iter().all(|&count| count >= 1), "{counts:?}");
+    }
+
+    #[test]
+    fn tied_species_split_evenly() {
+        assert_eq!(population(config(20), &[3.0, 3.0]).offspring_counts(), vec![10, 10]);
+    }
+
+    #[test]
+    fn stagnant_species_are_culled_except_the_best() {
+        let config = config(20).with_stagnation(NonZeroUsize::new(3).unwrap(), 0);
+        let mut population = population(config, &[1.0, 5.0, 9.0]);
+
+        for (species, stagnation) in population.species.iter_mut().zip([0, 10, 10]) {
+            species.stagnation = stagnation;
+        }
+
+        population.cull_stagnant();
+
+        let kept = population.species.iter().map(|species| species.best_fitness.unwrap()).collect::<Vec<_>>();
+        assert_eq!(kept, vec![9.0, 1.0]);
+    }
+
+    #[test]
+    fn comp_thresh_steps_towards_the_species_target() {
+        // Three species against a target of one raise the threshold so that species merge, and against a target of
+        // five lower it so that more split off. On target, it stays put.
+        for (count, expected) in [(1, 3.5), (5, 2.5), (3, 3.0)] {
+            let target = SpeciesTarget { count, min_thresh: 2.0, max_thresh: 3.5, step: 0.5 };
+            let mut population = population(config(20).with_species_target(target), &[1.0, 2.0, 3.0]);
+
+            population.adjust_comp_thresh();
+            assert_eq!(population.comp_thresh, expected, "target of {count}");
+
+            // Further steps never leave the bounds.
+            for _ in 0..5 {
+                population.adjust_comp_thresh();
+                assert!((2.0..=3.5).contains(&population.comp_thresh));
+            }
+        }
+    }
+
+    #[test]
+    fn parsimony_charges_every_hidden_node_and_enabled_conn() {
+        let config = config(20).with_initial_topology(InitialTopology::Hidden(1)).with_parsimony(0.5, 0.1);
+        let mut population = population(config, &[10.0]);
+
+        let genome = &mut population.species[0].members[0];
+        genome.conn_genes().next().unwrap().set_enabled(false);
+        genome.set_fitness(10.0, &population.config);
+
+        population.apply_parsimony();
+
+        // One hidden node and two of the three connections are charged.
+        assert!((population.species[0].members[0].fitness().unwrap() - (10.0 - 0.5 - 0.2)).abs() < 1e-6);
+    }
 }
\ No newline at end of file
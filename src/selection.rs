@@ -0,0 +1,102 @@
+use std::num::NonZeroUsize;
+use crate::traits;
+use rand::{distributions::WeightedIndex, prelude::Distribution, seq::SliceRandom, Rng, RngCore};
+
+/// Picks the fittest of `size` members drawn at random.
+#[derive(Clone, Copy, Debug)]
+pub struct Tournament {
+    size: usize,
+}
+
+impl Tournament {
+    pub fn new(size: NonZeroUsize) -> Self {
+        Self { size: size.into() }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl<G: traits::Genome> traits::Selection<G> for Tournament {
+    fn select<'a>(&self, parents: &[&'a G], rng: &mut dyn RngCore) -> &'a G {
+        let best = (0..self.size).map(|_| rng.gen_range(0..parents.len())).min().unwrap();
+        parents[best]
+    }
+}
+
+/// Picks members with a probability proportional to their fitness, shifted so that the least fit member has none
+/// unless every member is equally fit.
+#[derive(Clone, Copy, Debug)]
+pub struct FitnessProportionate;
+
+impl<G: traits::Genome> traits::Selection<G> for FitnessProportionate {
+    fn select<'a>(&self, parents: &[&'a G], rng: &mut dyn RngCore) -> &'a G {
+        let min = parents.last().unwrap().fitness().unwrap();
+
+        match WeightedIndex::new(parents.iter().map(|parent| parent.fitness().unwrap() - min)) {
+            Ok(weights) => parents[weights.sample(rng)],
+            Err(_) => parents.choose(rng).unwrap(),
+        }
+    }
+}
+
+/// Picks members by linear ranking. `pressure` ranges from `1.0`, where every rank is equally likely, to `2.0`, where
+/// the least fit member is never picked and the fittest is twice as likely as the average.
+#[derive(Clone, Copy, Debug)]
+pub struct RankBased {
+    pressure: f32,
+}
+
+impl RankBased {
+    pub fn new(pressure: f32) -> Self {
+        assert!((1.0..=2.0).contains(&pressure), "rank-based selection pressure must be in [1, 2]");
+        Self { pressure }
+    }
+
+    pub fn pressure(&self) -> f32 {
+        self.pressure
+    }
+}
+
+impl<G: traits::Genome> traits::Selection<G> for RankBased {
+    fn select<'a>(&self, parents: &[&'a G], rng: &mut dyn RngCore) -> &'a G {
+        let last = (parents.len() - 1).max(1) as f32;
+        let weights = (0..parents.len()).map(|rank| 2.0 - self.pressure + 2.0 * (self.pressure - 1.0) * (last - rank as f32) / last);
+
+        match WeightedIndex::new(weights) {
+            Ok(weights) => parents[weights.sample(rng)],
+            Err(_) => parents.choose(rng).unwrap(),
+        }
+    }
+}
+
+/// Picks uniformly among the fittest `fraction` of members, and at least the fittest one.
+#[derive(Clone, Copy, Debug)]
+pub struct Truncation {
+    fraction: f32,
+}
+
+impl Truncation {
+    pub fn new(fraction: f32) -> Self {
+        assert!(fraction > 0.0 && fraction <= 1.0, "truncation fraction must be in (0, 1]");
+        Self { fraction }
+    }
+
+    pub fn fraction(&self) -> f32 {
+        self.fraction
+    }
+}
+
+impl Default for Truncation {
+    fn default() -> Self {
+        Self { fraction: 1.0 }
+    }
+}
+
+impl<G: traits::Genome> traits::Selection<G> for Truncation {
+    fn select<'a>(&self, parents: &[&'a G], rng: &mut dyn RngCore) -> &'a G {
+        let survivors = ((parents.len() as f32 * self.fraction).ceil() as usize).clamp(1, parents.len());
+        parents[rng.gen_range(0..survivors)]
+    }
+}
@@ -0,0 +1,119 @@
+use crate::traits;
+use rand::seq::SliceRandom;
+
+/// Which member stands for a species when the next generation is sorted into species.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Representative {
+    #[default]
+    Random,
+    Best,
+}
+
+/// The speciation from the NEAT paper. Each genome joins the first species whose representative is within the
+/// compatibility threshold of it, and founds a new species if there is none.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Classic {
+    pub representative: Representative,
+}
+
+impl<G: traits::Genome> traits::Speciation<G> for Classic {
+    fn representative<'a>(&self, members: &[&'a G]) -> &'a G {
+        match self.representative {
+            Representative::Random => members.choose(&mut rand::thread_rng()).unwrap(),
+            Representative::Best => members.first().unwrap(),
+        }
+    }
+
+    fn speciate(&self, genomes: &[G], representatives: &[&G], comp_thresh: f32, config: &G::Config) -> Vec<usize> {
+        let mut representatives = representatives.to_vec();
+
+        genomes.iter().map(|genome| {
+            match representatives.iter().position(|representative| genome.comp_dist(representative, config) < comp_thresh) {
+                Some(species) => species,
+                None => {
+                    representatives.push(genome);
+                    representatives.len() - 1
+                }
+            }
+        }).collect()
+    }
+}
+
+/// Clusters genomes into `count` species with k-medoids over the compatibility distance, ignoring the compatibility
+/// threshold. There are fewer species when there are fewer than `count` distinct genomes. Each cluster keeps the identity of the closest remaining species from the previous generation, so that
+/// species history such as stagnation carries over.
+#[derive(Clone, Copy, Debug)]
+pub struct KMedoids {
+    pub count: usize,
+    pub iterations: usize,
+}
+
+impl<G: traits::Genome> traits::Speciation<G> for KMedoids {
+    fn representative<'a>(&self, members: &[&'a G]) -> &'a G {
+        members.first().unwrap()
+    }
+
+    fn speciate(&self, genomes: &[G], representatives: &[&G], _comp_thresh: f32, config: &G::Config) -> Vec<usize> {
+        let distances = genomes.iter()
+            .map(|genome| genomes.iter().map(|other| genome.comp_dist(other, config)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let nearest = |medoids: &[usize], genome: usize| (0..medoids.len())
+            .min_by(|&a, &b| distances[genome][medoids[a]].total_cmp(&distances[genome][medoids[b]]))
+            .unwrap();
+
+        // Identical genomes tie for every member, which would leave all but one of their clusters empty, so the initial
+        // medoids are drawn among distinct genomes only.
+        let mut order = (0..genomes.len()).collect::<Vec<_>>();
+        order.shuffle(&mut rand::thread_rng());
+
+        let mut medoids = Vec::new();
+
+        for genome in order {
+            if medoids.len() < self.count.max(1) && medoids.iter().all(|&medoid| distances[genome][medoid] > 0.0) {
+                medoids.push(genome);
+            }
+        }
+
+        let k = medoids.len();
+        let mut clusters = (0..genomes.len()).map(|genome| nearest(&medoids, genome)).collect::<Vec<_>>();
+
+        for _ in 0..self.iterations {
+            let new_medoids = (0..k).map(|cluster| {
+                let members = (0..genomes.len()).filter(|&genome| clusters[genome] == cluster).collect::<Vec<_>>();
+
+                members.iter().copied()
+                    .min_by(|&a, &b| {
+                        let cost = |medoid: usize| members.iter().map(|&member| distances[medoid][member]).sum::<f32>();
+                        cost(a).total_cmp(&cost(b))
+                    })
+                    .unwrap_or(medoids[cluster])
+            }).collect::<Vec<_>>();
+
+            if new_medoids == medoids {
+                break;
+            }
+
+            medoids = new_medoids;
+            clusters = (0..genomes.len()).map(|genome| nearest(&medoids, genome)).collect();
+        }
+
+        // Hand each cluster the closest species that no other cluster has claimed yet, or a new one.
+        let mut unclaimed = (0..representatives.len()).collect::<Vec<_>>();
+
+        let species = medoids.iter().enumerate().map(|(cluster, &medoid)| {
+            let closest = unclaimed.iter().enumerate()
+                .min_by(|(_, &a), (_, &b)| {
+                    genomes[medoid].comp_dist(representatives[a], config).total_cmp(&genomes[medoid].comp_dist(representatives[b], config))
+                })
+                .map(|(i, _)| i);
+
+            match closest {
+                Some(i) => unclaimed.remove(i),
+                None => representatives.len() + cluster,
+            }
+        }).collect::<Vec<_>>();
+
+        clusters.into_iter().map(|cluster| species[cluster]).collect()
+    }
+}
@@ -0,0 +1,353 @@
+use std::{collections::{BTreeMap, BTreeSet}, error, fmt};
+use crate::{Config, FeedForwardGenome, traits::{Config as _, ConnGene, Genome, NodeGene}};
+
+/// A broken invariant found by [`FeedForwardGenome::validate`]. Nodes are given by id and connections by innovation
+/// number.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InvariantError {
+    InputCount { expected: usize, found: usize },
+    OutputCount { expected: usize, found: usize },
+    /// Several nodes of the genome share an id.
+    DuplicateNode(u32),
+    /// A connection refers to a node that is not one of the genome's own, even if it shares an id with one.
+    ForeignNode { innov: u32, node: u32 },
+    /// A connection is missing from the forward set of its in node.
+    MissingForward { innov: u32, node: u32 },
+    /// A connection is missing from the backward set of its out node.
+    MissingBackward { innov: u32, node: u32 },
+    /// A node's forward set holds a connection that is not one of the genome's own or that starts at another node.
+    StrayForward { node: u32, innov: u32 },
+    /// A node's backward set holds a connection that is not one of the genome's own or that ends at another node.
+    StrayBackward { node: u32, innov: u32 },
+    /// Distinct connections share an innovation number.
+    DuplicateInnov(u32),
+    /// The same pair of nodes is connected more than once.
+    DuplicateConn { in_node: u32, out_node: u32 },
+    /// A connection's innovation number differs from the one the config history records for its pair of nodes. Pairs
+    /// the history has no record of, such as those of a genome evolved with another config, are not checked.
+    InnovMismatch { in_node: u32, out_node: u32, innov: u32, recorded: u32 },
+    /// The connections, disabled ones included, form a cycle through these nodes. Nodes that are only downstream of a
+    /// cycle are left out.
+    Cycle(Vec<u32>),
+}
+
+impl fmt::Display for InvariantError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InputCount { expected, found } => write!(f, "expected {expected} input nodes, found {found}"),
+            Self::OutputCount { expected, found } => write!(f, "expected {expected} output nodes, found {found}"),
+            Self::DuplicateNode(node) => write!(f, "node id {node} is used more than once"),
+            Self::ForeignNode { innov, node } => write!(f, "connection #{innov} refers to node {node}, which is not in the genome"),
+            Self::MissingForward { innov, node } => write!(f, "connection #{innov} is missing from the forward set of node {node}"),
+            Self::MissingBackward { innov, node } => write!(f, "connection #{innov} is missing from the backward set of node {node}"),
+            Self::StrayForward { node, innov } => write!(f, "the forward set of node {node} holds stray connection #{innov}"),
+            Self::StrayBackward { node, innov } => write!(f, "the backward set of node {node} holds stray connection #{innov}"),
+            Self::DuplicateInnov(innov) => write!(f, "innovation #{innov} is shared by distinct connections"),
+            Self::DuplicateConn { in_node, out_node } => write!(f, "nodes {in_node} -> {out_node} are connected more than once"),
+            Self::InnovMismatch { in_node, out_node, innov, recorded } =>
+                write!(f, "connection {in_node} -> {out_node} has innovation #{innov}, but the config records #{recorded}"),
+            Self::Cycle(nodes) => write!(f, "the connections form a cycle through nodes {nodes:?}"),
+        }
+    }
+}
+
+impl error::Error for InvariantError { }
+
+impl FeedForwardGenome {
+    /// Checks the invariants that tie the genome's nodes and connections together, and that it fits `config`, returning
+    /// every broken one. Meant to be run in debug builds after mutations.
+    pub fn validate(&self, config: &Config<Self>) -> Result<(), Vec<InvariantError>> {
+        let mut errors = Vec::new();
+
+        let (inputs, outputs) = (self.input_nodes().count(), self.output_nodes().count());
+
+        if inputs != config.input_len() {
+            errors.push(InvariantError::InputCount { expected: config.input_len(), found: inputs });
+        }
+
+        if outputs != config.output_len() {
+            errors.push(InvariantError::OutputCount { expected: config.output_len(), found: outputs });
+        }
+
+        let nodes = self.input_nodes().chain(self.hidden_nodes()).chain(self.output_nodes()).collect::<Vec<_>>();
+        let mut ids = BTreeSet::new();
+
+        for node in nodes.iter().filter(|node| !ids.insert(node.id())) {
+            errors.push(InvariantError::DuplicateNode(node.id()));
+        }
+
+        let conns = self.conn_genes().collect::<Vec<_>>();
+        let mut pairs = BTreeSet::new();
+
+        for &conn in conns.iter() {
+            let (in_node, out_node) = (conn.in_node(), conn.out_node());
+
+            for node in [&in_node, &out_node] {
+                if !nodes.iter().any(|own| own.ptr_eq(node)) {
+                    errors.push(InvariantError::ForeignNode { innov: conn.innov(), node: node.id() });
+                }
+            }
+
+            if !in_node.forward(|forward| forward.iter().any(|other| other.eq(conn))) {
+                errors.push(InvariantError::MissingForward { innov: conn.innov(), node: in_node.id() });
+            }
+
+            if !out_node.backward(|backward| backward.iter().any(|other| other.eq(conn))) {
+                errors.push(InvariantError::MissingBackward { innov: conn.innov(), node: out_node.id() });
+            }
+
+            if !pairs.insert((in_node.id(), out_node.id())) {
+                errors.push(InvariantError::DuplicateConn { in_node: in_node.id(), out_node: out_node.id() });
+            }
+
+            let recorded = config.recorded_innov(in_node.id(), out_node.id());
+
+            if let Some(recorded) = recorded.filter(|&recorded| recorded != conn.innov()) {
+                errors.push(InvariantError::InnovMismatch { in_node: in_node.id(), out_node: out_node.id(), innov: conn.innov(), recorded });
+            }
+        }
+
+        // Every distinct connection reachable from the genome, whether through its own set or a node's adjacency sets.
+        let mut reachable = conns.iter().map(|&conn| conn.clone()).collect::<Vec<_>>();
+
+        for node in nodes.iter() {
+            for conn in node.forward(|forward| forward.iter().cloned().collect::<Vec<_>>()) {
+                if !conns.iter().any(|&own| own.eq(&conn)) || conn.in_node().ne(node) {
+                    errors.push(InvariantError::StrayForward { node: node.id(), innov: conn.innov() });
+                }
+
+                reachable.push(conn);
+            }
+
+            for conn in node.backward(|backward| backward.iter().cloned().collect::<Vec<_>>()) {
+                if !conns.iter().any(|&own| own.eq(&conn)) || conn.out_node().ne(node) {
+                    errors.push(InvariantError::StrayBackward { node: node.id(), innov: conn.innov() });
+                }
+
+                reachable.push(conn);
+            }
+        }
+
+        let mut by_innov = BTreeMap::<u32, Vec<_>>::new();
+
+        for conn in reachable {
+            let same = by_innov.entry(conn.innov()).or_default();
+
+            if !same.iter().any(|other| conn.eq(other)) {
+                same.push(conn);
+            }
+        }
+
+        for (innov, _) in by_innov.iter().filter(|(_, same)| same.len() > 1) {
+            errors.push(InvariantError::DuplicateInnov(*innov));
+        }
+
+        // Peel off nodes without incoming connections from other non-input nodes. Whatever is left sits on a cycle or
+        // downstream of one, so only the nodes that lead back to themselves are reported.
+        let input_ids = BTreeSet::from_iter(self.input_nodes().map(|node| node.id()));
+        let mut in_degree = BTreeMap::from_iter(self.hidden_nodes().chain(self.output_nodes()).map(|node| (node.id(), 0)));
+
+        for &(in_node, out_node) in pairs.iter().filter(|(in_node, _)| !input_ids.contains(in_node)) {
+            if in_degree.contains_key(&in_node) {
+                in_degree.entry(out_node).and_modify(|degree| *degree += 1);
+            }
+        }
+
+        let mut ready = in_degree.iter().filter(|(_, degree)| degree.eq(&&0)).map(|(&node, _)| node).collect::<Vec<_>>();
+
+        while let Some(node) = ready.pop() {
+            in_degree.remove(&node);
+
+            for (_, out_node) in pairs.iter().filter(|(in_node, _)| in_node.eq(&node)) {
+                if let Some(degree) = in_degree.get_mut(out_node) {
+                    *degree -= 1;
+
+                    if *degree == 0 {
+                        ready.push(*out_node);
+                    }
+                }
+            }
+        }
+
+        let left = in_degree.into_keys().collect::<BTreeSet<_>>();
+
+        let on_cycle = left.iter().copied().filter(|&node| {
+            let (mut stack, mut seen) = (vec![node], BTreeSet::new());
+
+            while let Some(from) = stack.pop() {
+                for &(_, out_node) in pairs.iter().filter(|(in_node, _)| in_node.eq(&from)) {
+                    if out_node == node {
+                        return true;
+                    }
+
+                    if left.contains(&out_node) && seen.insert(out_node) {
+                        stack.push(out_node);
+                    }
+                }
+            }
+
+            false
+        }).collect::<Vec<_>>();
+
+        if !on_cycle.is_empty() {
+            errors.push(InvariantError::Cycle(on_cycle));
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+    use crate::{Config, ConnGene, FeedForwardGenome, NodeGene, traits::{Config as _, ConnGene as _, Genome, NodeGene as _}};
+    use super::InvariantError;
+
+    fn config(input_len: usize, output_len: usize) -> Config<FeedForwardGenome> {
+        Config::new(NonZeroUsize::new(input_len).unwrap(), NonZeroUsize::new(output_len).unwrap(), NonZeroUsize::new(20).unwrap(), 3.0, 1.0, 1.0, 0.4)
+    }
+
+    /// Builds a genome with inputs 0 and 1, outputs 2 and 3, and the given hidden nodes and enabled connections.
+    fn genome(config: &Config<FeedForwardGenome>, hidden: &[u32], conns: &[(u32, u32)]) -> FeedForwardGenome {
+        let conns = conns.iter().map(|&(in_node, out_node)| (in_node, out_node, 1.0, true)).collect::<Vec<_>>();
+        FeedForwardGenome::from_genes(config, hidden, &conns)
+    }
+
+    fn only_conn(genome: &FeedForwardGenome) -> ConnGene<FeedForwardGenome> {
+        genome.conn_genes().next().unwrap().clone()
+    }
+
+    fn node(genome: &FeedForwardGenome, id: u32) -> NodeGene<FeedForwardGenome> {
+        genome.input_nodes().chain(genome.hidden_nodes()).chain(genome.output_nodes()).find(|node| node.id() == id).unwrap().clone()
+    }
+
+    #[test]
+    fn wrong_input_count() {
+        let genome = genome(&config(2, 2), &[], &[]);
+        assert_eq!(genome.validate(&config(3, 2)), Err(vec![InvariantError::InputCount { expected: 3, found: 2 }]));
+    }
+
+    #[test]
+    fn wrong_output_count() {
+        let genome = genome(&config(2, 2), &[], &[]);
+        assert_eq!(genome.validate(&config(2, 1)), Err(vec![InvariantError::OutputCount { expected: 1, found: 2 }]));
+    }
+
+    #[test]
+    fn duplicate_node() {
+        let config = config(2, 2);
+        assert_eq!(genome(&config, &[0], &[]).validate(&config), Err(vec![InvariantError::DuplicateNode(0)]));
+    }
+
+    #[test]
+    fn foreign_node() {
+        let config = config(2, 2);
+        let mut genome = genome(&config, &[], &[(0, 2)]);
+
+        // The stand-in shares its id with an input of the genome, but it is not that input.
+        let (stand_in, out_node) = (NodeGene::new_input(1), node(&genome, 2));
+        let innov = config.innov(stand_in.clone(), out_node.clone());
+        genome.insert_conn(ConnGene::new(stand_in, out_node, 1.0, innov));
+
+        assert_eq!(genome.validate(&config), Err(vec![InvariantError::ForeignNode { innov, node: 1 }]));
+    }
+
+    #[test]
+    fn missing_forward() {
+        let config = config(2, 2);
+        let genome = genome(&config, &[], &[(0, 2)]);
+        let conn = only_conn(&genome);
+
+        node(&genome, 0).forward_mut(|forward| forward.remove(&conn));
+        assert_eq!(genome.validate(&config), Err(vec![InvariantError::MissingForward { innov: conn.innov(), node: 0 }]));
+    }
+
+    #[test]
+    fn missing_backward() {
+        let config = config(2, 2);
+        let genome = genome(&config, &[], &[(0, 2)]);
+        let conn = only_conn(&genome);
+
+        node(&genome, 2).backward_mut(|backward| backward.remove(&conn));
+        assert_eq!(genome.validate(&config), Err(vec![InvariantError::MissingBackward { innov: conn.innov(), node: 2 }]));
+    }
+
+    #[test]
+    fn stray_forward() {
+        let config = config(2, 2);
+        let genome = genome(&config, &[], &[(0, 2)]);
+        let conn = only_conn(&genome);
+
+        node(&genome, 1).forward_mut(|forward| forward.insert(conn.clone()));
+        assert_eq!(genome.validate(&config), Err(vec![InvariantError::StrayForward { node: 1, innov: conn.innov() }]));
+    }
+
+    #[test]
+    fn stray_backward() {
+        let config = config(2, 2);
+        let genome = genome(&config, &[], &[(0, 2)]);
+        let conn = only_conn(&genome);
+
+        node(&genome, 3).backward_mut(|backward| backward.insert(conn.clone()));
+        assert_eq!(genome.validate(&config), Err(vec![InvariantError::StrayBackward { node: 3, innov: conn.innov() }]));
+    }
+
+    #[test]
+    fn duplicate_innov() {
+        let config = config(2, 2);
+        let genome = genome(&config, &[], &[(0, 2)]);
+        let innov = only_conn(&genome).innov();
+
+        // A connection that is not the genome's own but reuses its innovation number is also stray.
+        let impostor = ConnGene::new(node(&genome, 1), node(&genome, 3), 1.0, innov);
+        node(&genome, 1).forward_mut(|forward| forward.insert(impostor.clone()));
+
+        assert_eq!(genome.validate(&config), Err(vec![
+            InvariantError::StrayForward { node: 1, innov },
+            InvariantError::DuplicateInnov(innov),
+        ]));
+    }
+
+    #[test]
+    fn duplicate_conn() {
+        let config = config(2, 2);
+        let mut genome = genome(&config, &[], &[(0, 2)]);
+        let recorded = only_conn(&genome).innov();
+
+        // The second connection between the same nodes cannot have the innovation number the history records as well.
+        let innov = config.innov(node(&genome, 1), node(&genome, 3));
+        genome.insert_conn(ConnGene::new(node(&genome, 0), node(&genome, 2), 1.0, innov));
+
+        assert_eq!(genome.validate(&config), Err(vec![
+            InvariantError::DuplicateConn { in_node: 0, out_node: 2 },
+            InvariantError::InnovMismatch { in_node: 0, out_node: 2, innov, recorded },
+        ]));
+    }
+
+    #[test]
+    fn innov_mismatch_only_for_pairs_in_the_history() {
+        let genome = genome(&config(2, 2), &[], &[(0, 2)]);
+        let innov = only_conn(&genome).innov();
+
+        // A config without any history has nothing to compare against.
+        let config = config(2, 2);
+        assert_eq!(genome.validate(&config), Ok(()));
+
+        config.innov(node(&genome, 1), node(&genome, 3));
+        let recorded = config.innov(node(&genome, 0), node(&genome, 2));
+        assert_ne!(innov, recorded);
+
+        assert_eq!(genome.validate(&config), Err(vec![InvariantError::InnovMismatch { in_node: 0, out_node: 2, innov, recorded }]));
+    }
+
+    #[test]
+    fn cycle_leaves_out_downstream_nodes() {
+        let config = config(2, 2);
+        let genome = genome(&config, &[4, 5, 6], &[(0, 4), (4, 5), (5, 4), (5, 6), (6, 2)]);
+
+        assert_eq!(genome.validate(&config), Err(vec![InvariantError::Cycle(vec![4, 5])]));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,98 @@
+mod common;
+
+use bevy_neat::{BuildError, Config, FeedForwardGenome, GenomeBuilder, traits::Genome};
+
+const XOR_INPUTS: [(f32, f32); 4] = [(0.0, 0.0), (0.0, 1.0), (1.0, 0.0), (1.0, 1.0)];
+const XOR_OUTPUTS: [f32; 4] = [0.0, 1.0, 1.0, 0.0];
+
+// The steepness of the hidden and output nodes.
+const HIDDEN_GAIN: f32 = 100.0;
+const OUTPUT_GAIN: f32 = 20.0;
+
+/// Hand-builds a network that solves XOR with sigmoid activations.
+///
+/// Inputs pass through the sigmoid as well, so they arrive as `0.5` for `0` and `sigmoid(1)` for `1`. There is no
+/// bias node, so a hidden node without incoming connections, which always outputs `0.5`, stands in for one. One hidden
+/// node fires when either input is set and the other only when both are, and the output fires when the first does but
+/// the second doesn't.
+fn xor(config: &Config<FeedForwardGenome>) -> FeedForwardGenome {
+    let step = 1.0 / (1.0 + (-1.0f32).exp()) - 0.5;
+
+    let mut builder = GenomeBuilder::new(config);
+    let (x, y, output) = (builder.input(0), builder.input(1), builder.output(0));
+    let (bias, or, and) = (builder.hidden(), builder.hidden(), builder.hidden());
+
+    builder
+        .conn(x, or, HIDDEN_GAIN)
+        .conn(y, or, HIDDEN_GAIN)
+        .conn(bias, or, -2.0 * HIDDEN_GAIN * (1.0 + 0.5 * step))
+        .conn(x, and, HIDDEN_GAIN)
+        .conn(y, and, HIDDEN_GAIN)
+        .conn(bias, and, -2.0 * HIDDEN_GAIN * (1.0 + 1.5 * step))
+        .conn(or, output, OUTPUT_GAIN)
+        .conn(and, output, -OUTPUT_GAIN)
+        .conn(bias, output, -OUTPUT_GAIN);
+
+    builder.build().unwrap()
+}
+
+#[test]
+fn hand_built_xor_solves_xor() {
+    let config = common::config(2, 1);
+    let genome = xor(&config);
+
+    assert_eq!(genome.validate(&config), Ok(()));
+
+    for ((x, y), expected) in XOR_INPUTS.into_iter().zip(XOR_OUTPUTS) {
+        let output = genome.activate(vec![x, y], &config)[0];
+        assert!((output - expected).abs() < 0.01, "{x} xor {y} gave {output}");
+    }
+}
+
+#[test]
+fn unknown_node_is_rejected() {
+    let config = common::config(2, 1);
+    let mut builder = GenomeBuilder::new(&config);
+    builder.conn(0, 99, 1.0);
+
+    assert_eq!(builder.build().unwrap_err(), BuildError::UnknownNode(99));
+}
+
+#[test]
+fn conn_into_input_is_rejected() {
+    let config = common::config(2, 1);
+    let mut builder = GenomeBuilder::new(&config);
+    let hidden = builder.hidden();
+    builder.conn(hidden, 0, 1.0);
+
+    assert_eq!(builder.build().unwrap_err(), BuildError::ConnIntoInput { in_node: hidden, out_node: 0 });
+}
+
+#[test]
+fn conn_from_output_is_rejected() {
+    let config = common::config(2, 1);
+    let mut builder = GenomeBuilder::new(&config);
+    let hidden = builder.hidden();
+    builder.conn(2, hidden, 1.0);
+
+    assert_eq!(builder.build().unwrap_err(), BuildError::ConnFromOutput { in_node: 2, out_node: hidden });
+}
+
+#[test]
+fn duplicate_conn_is_rejected() {
+    let config = common::config(2, 1);
+    let mut builder = GenomeBuilder::new(&config);
+    builder.conn(0, 2, 1.0).disabled_conn(0, 2, -1.0);
+
+    assert_eq!(builder.build().unwrap_err(), BuildError::DuplicateConn { in_node: 0, out_node: 2 });
+}
+
+#[test]
+fn cycle_is_rejected_even_through_disabled_conns() {
+    let config = common::config(2, 1);
+    let mut builder = GenomeBuilder::new(&config);
+    let (a, b) = (builder.hidden(), builder.hidden());
+    builder.conn(0, a, 1.0).conn(a, b, 1.0).disabled_conn(b, a, 1.0).conn(b, 2, 1.0);
+
+    assert_eq!(builder.build().unwrap_err(), BuildError::Cycle);
+}
\ No newline at end of file
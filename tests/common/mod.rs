@@ -0,0 +1,15 @@
+// Each integration test crate compiles this module on its own and only uses part of it.
+#![allow(dead_code)]
+
+use std::num::NonZeroUsize;
+use bevy_neat::{Config, FeedForwardGenome, InitialTopology};
+
+/// A config with a population of 20 and the default compatibility coefficients, whose minimal genomes are unconnected.
+pub fn config(input_len: usize, output_len: usize) -> Config<FeedForwardGenome> {
+    Config::new(NonZeroUsize::new(input_len).unwrap(), NonZeroUsize::new(output_len).unwrap(), NonZeroUsize::new(20).unwrap(), 3.0, 1.0, 1.0, 0.4)
+}
+
+/// Like [`config`], but minimal genomes connect every input to every output.
+pub fn connected_config(input_len: usize, output_len: usize) -> Config<FeedForwardGenome> {
+    config(input_len, output_len).with_initial_topology(InitialTopology::FullyConnected)
+}
\ No newline at end of file
@@ -0,0 +1,98 @@
+mod common;
+
+use bevy_neat::{Config, FeedForwardGenome, InitialTopology, SpeciesTarget, WeightInit, traits::{Config as _, ConnGene, Genome, NodeGene}};
+
+#[test]
+fn single_value_uniform_init_gives_constant_weights() {
+    let config = common::connected_config(3, 2).with_weight_init(WeightInit::Uniform { min: 0.5, max: 0.5 });
+    let genome = FeedForwardGenome::minimal(&config);
+
+    assert_eq!(genome.conn_genes().count(), 6);
+    assert!(genome.conn_genes().all(|conn| conn.weight() == 0.5));
+}
+
+#[test]
+#[should_panic(expected = "uniform weight init needs min <= max")]
+fn uniform_init_rejects_reversed_range() {
+    common::connected_config(3, 2).with_weight_init(WeightInit::Uniform { min: 1.0, max: -1.0 });
+}
+
+#[test]
+#[should_panic(expected = "normal weight init needs a non-negative std_dev")]
+fn normal_init_rejects_negative_std_dev() {
+    common::connected_config(3, 2).with_weight_init(WeightInit::Normal { mean: 0.0, std_dev: -1.0 });
+}
+
+#[test]
+#[should_panic(expected = "species target count must be positive")]
+fn species_target_rejects_zero_count() {
+    common::config(2, 1).with_species_target(SpeciesTarget { count: 0, min_thresh: 1.0, max_thresh: 5.0, step: 0.5 });
+}
+
+#[test]
+#[should_panic(expected = "species target step must be positive and finite")]
+fn species_target_rejects_non_positive_step() {
+    common::config(2, 1).with_species_target(SpeciesTarget { count: 5, min_thresh: 1.0, max_thresh: 5.0, step: 0.0 });
+}
+
+#[test]
+#[should_panic(expected = "initial topology has more hidden nodes than the complexity limit")]
+fn initial_hidden_nodes_must_fit_the_limit() {
+    common::config(2, 1).with_initial_topology(InitialTopology::Hidden(3)).with_complexity_limits(2, 100);
+}
+
+#[test]
+#[should_panic(expected = "initial topology has more connections than the complexity limit")]
+fn initial_conns_must_fit_the_limit_whichever_is_set_first() {
+    common::config(3, 2).with_complexity_limits(10, 5).with_initial_topology(InitialTopology::FullyConnected);
+}
+
+#[test]
+fn initial_topology_gives_the_same_ids_in_any_order() {
+    let before = common::config(2, 1).with_initial_topology(InitialTopology::Hidden(2)).with_complexity_limits(4, 20);
+    let after = common::config(2, 1).with_complexity_limits(4, 20).with_initial_topology(InitialTopology::Hidden(2));
+
+    let ids = |config: &Config<FeedForwardGenome>| {
+        let genome = FeedForwardGenome::minimal(config);
+        (genome.hidden_nodes().map(|node| node.id()).collect::<Vec<_>>(), config.node_id())
+    };
+
+    // The hidden nodes of minimal genomes directly follow the outputs, and new ids come after them.
+    assert_eq!(ids(&before), (vec![3, 4], 5));
+    assert_eq!(ids(&after), (vec![3, 4], 5));
+}
+
+#[test]
+#[should_panic(expected = "uniform weight init needs finite bounds")]
+fn uniform_init_rejects_infinite_bounds() {
+    common::config(2, 1).with_weight_init(WeightInit::Uniform { min: f32::NEG_INFINITY, max: 1.0 });
+}
+
+#[test]
+fn default_init_draws_weights_of_both_signs() {
+    let config = common::connected_config(3, 2);
+    let weights = (0..20).flat_map(|_| FeedForwardGenome::minimal(&config).conn_genes().map(|conn| conn.weight()).collect::<Vec<_>>()).collect::<Vec<_>>();
+
+    assert!(weights.iter().all(|weight| (-1.0..=1.0).contains(weight)));
+    assert!(weights.iter().any(|&weight| weight < 0.0) && weights.iter().any(|&weight| weight > 0.0));
+}
+
+#[test]
+fn normal_init_and_mutations_stay_inside_the_weight_bounds() {
+    let config = common::connected_config(3, 2)
+        .with_weight_init(WeightInit::Normal { mean: 0.5, std_dev: 10.0 })
+        .with_weight_bounds(-2.0..=3.0);
+
+    for _ in 0..20 {
+        let mut genome = FeedForwardGenome::minimal(&config);
+
+        for _ in 0..20 {
+            genome.add_node_mut(&config);
+            genome.add_conn_mut(&config);
+            genome.mut_conn_weight(&config);
+            genome.jitter_weights(5.0, &config);
+        }
+
+        assert!(genome.conn_genes().all(|conn| (-2.0..=3.0).contains(&conn.weight())));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,49 @@
+mod common;
+
+use bevy_neat::{FeedForwardGenome, GenomeBuilder, GenomeDiff, traits::{ConnGene, Genome, NodeGene}};
+
+fn innov(genome: &FeedForwardGenome, in_node: u32, out_node: u32) -> u32 {
+    genome.conn_genes().find(|conn| conn.in_node().id() == in_node && conn.out_node().id() == out_node).unwrap().innov()
+}
+
+#[test]
+fn diff_of_two_known_genomes() {
+    let config = common::config(3, 1);
+
+    let mut builder = GenomeBuilder::new(&config);
+    let (a, b, c, x) = (builder.input(0), builder.input(1), builder.input(2), builder.output(0));
+    let removed = builder.hidden();
+
+    builder.conn(a, x, 1.0).conn(b, x, 1.0).conn(c, x, 1.0).conn(a, removed, 1.0).conn(removed, x, 1.0);
+    let parent = builder.build().unwrap();
+
+    let mut builder = GenomeBuilder::new(&config);
+    let added = builder.hidden();
+
+    // The weight of the connection from `c` moves by less than the threshold.
+    builder.conn(a, x, 1.5).disabled_conn(b, x, 1.0).conn(c, x, 1.05).conn(a, added, 1.0).conn(added, x, 1.0);
+    let child = builder.build().unwrap();
+
+    let diff = parent.diff(&child, 0.1);
+
+    assert_eq!(diff, GenomeDiff {
+        added_conns: vec![innov(&child, a, added), innov(&child, added, x)],
+        removed_conns: vec![innov(&parent, a, removed), innov(&parent, removed, x)],
+        enabled_changes: vec![(innov(&child, b, x), false)],
+        weight_changes: vec![(innov(&child, a, x), 1.0, 1.5)],
+        added_nodes: vec![added],
+        removed_nodes: vec![removed],
+    });
+
+    // Going back undoes every change.
+    assert_eq!(child.diff(&parent, 0.1), GenomeDiff {
+        added_conns: diff.removed_conns,
+        removed_conns: diff.added_conns,
+        enabled_changes: vec![(innov(&parent, b, x), true)],
+        weight_changes: vec![(innov(&parent, a, x), 1.5, 1.0)],
+        added_nodes: diff.removed_nodes,
+        removed_nodes: diff.added_nodes,
+    });
+
+    assert!(parent.diff(&parent.clone(), 0.0).is_empty());
+}
\ No newline at end of file
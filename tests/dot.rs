@@ -0,0 +1,50 @@
+mod common;
+
+use bevy_neat::{GenomeBuilder, to_dot};
+
+/// Returns the rank groups of a DOT graph, each with the lines declaring its nodes.
+fn rank_groups(dot: &str) -> Vec<Vec<&str>> {
+    dot.split("    {\n").skip(1).map(|group| group.split("    }\n").next().unwrap().lines().map(str::trim).collect()).collect()
+}
+
+#[test]
+fn dot_lists_nodes_edges_and_disabled_styling() {
+    let config = common::config(2, 1);
+    let mut builder = GenomeBuilder::new(&config);
+    let (x, y, output) = (builder.input(0), builder.input(1), builder.output(0));
+    let hidden = builder.hidden();
+
+    builder.conn(x, hidden, 0.5).conn(hidden, output, -1.25).disabled_conn(y, output, 2.0);
+    let dot = to_dot(&builder.build().unwrap(), Some(&["x", "y"]), None);
+
+    assert!(dot.starts_with("digraph genome {\n"));
+    assert!(dot.contains(&format!("n{x} [label=\"x\", shape=box];")));
+    assert!(dot.contains(&format!("n{y} [label=\"y\", shape=box];")));
+    assert!(dot.contains(&format!("n{hidden} [label=\"{hidden}\", shape=circle];")));
+    assert!(dot.contains(&format!("n{output} [label=\"out 0\", shape=doublecircle];")));
+
+    assert!(dot.contains(&format!("n{x} -> n{hidden} [label=\"0.500 (#0)\"];")));
+    assert!(dot.contains(&format!("n{hidden} -> n{output} [label=\"-1.250 (#1)\"];")));
+    assert!(dot.contains(&format!("n{y} -> n{output} [label=\"2.000 (#2)\", style=dashed];")));
+}
+
+#[test]
+fn hidden_nodes_get_one_rank_per_layer() {
+    let config = common::config(1, 1);
+    let mut builder = GenomeBuilder::new(&config);
+    let (input, output) = (builder.input(0), builder.output(0));
+    let (a, b, c) = (builder.hidden(), builder.hidden(), builder.hidden());
+
+    // `a` and `c` both sit right after the input, and `b` after `a`.
+    builder.conn(input, a, 1.0).conn(a, b, 1.0).conn(b, output, 1.0).conn(input, c, 1.0).conn(c, output, 1.0);
+    let dot = to_dot(&builder.build().unwrap(), None, None);
+
+    let groups = rank_groups(&dot);
+    let declares = |group: &[&str], node: u32| group.iter().any(|line| line.starts_with(&format!("n{node} ")));
+
+    assert_eq!(groups.len(), 4);
+    assert!(groups[0].contains(&"rank=source;") && declares(&groups[0], input));
+    assert!(groups[1].contains(&"rank=same;") && declares(&groups[1], a) && declares(&groups[1], c));
+    assert!(groups[2].contains(&"rank=same;") && declares(&groups[2], b));
+    assert!(groups[3].contains(&"rank=sink;") && declares(&groups[3], output));
+}
\ No newline at end of file
@@ -0,0 +1,70 @@
+mod common;
+
+use std::{collections::hash_map::DefaultHasher, hash::{Hash, Hasher}};
+use bevy_neat::{FeedForwardGenome, content_hash, structural_eq, traits::{ConnGene, Genome}};
+
+fn genome() -> FeedForwardGenome {
+    let config = common::connected_config(3, 2);
+
+    let mut genome = FeedForwardGenome::minimal(&config);
+    genome.add_node_mut(&config);
+    genome
+}
+
+fn std_hash(genome: &FeedForwardGenome) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    genome.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn clone_equals_and_hashes_like_the_original() {
+    let genome = genome();
+    let clone = genome.clone();
+
+    assert_eq!(genome, clone);
+    assert_eq!(std_hash(&genome), std_hash(&clone));
+    assert_eq!(content_hash(&genome, None), content_hash(&clone, None));
+    assert_eq!(content_hash(&genome, Some(0.1)), content_hash(&clone, Some(0.1)));
+}
+
+#[test]
+fn changing_one_weight_breaks_equality() {
+    let genome = genome();
+    let changed = genome.clone();
+
+    let conn = changed.conn_genes().next().unwrap();
+    conn.set_weight(conn.weight() + 1.0);
+
+    assert_ne!(genome, changed);
+    assert_ne!(content_hash(&genome, None), content_hash(&changed, None));
+    assert!(!structural_eq(&genome, &changed, Some(0.1)));
+}
+
+#[test]
+fn tolerance_rounds_weights_to_multiples_of_it() {
+    let genome = genome();
+    let (lhs, rhs) = (genome.clone(), genome.clone());
+
+    for (lhs, rhs) in lhs.conn_genes().zip(rhs.conn_genes()) {
+        lhs.set_weight(0.051);
+        rhs.set_weight(0.149);
+    }
+
+    assert!(structural_eq(&lhs, &rhs, Some(0.1)));
+    assert_eq!(content_hash(&lhs, Some(0.1)), content_hash(&rhs, Some(0.1)));
+
+    for (lhs, rhs) in lhs.conn_genes().zip(rhs.conn_genes()) {
+        lhs.set_weight(0.0499);
+        rhs.set_weight(0.0501);
+    }
+
+    assert!(!structural_eq(&lhs, &rhs, Some(0.1)));
+}
+
+#[test]
+#[should_panic(expected = "weight tolerance must be positive and finite")]
+fn zero_tolerance_is_rejected() {
+    let genome = genome();
+    structural_eq(&genome, &genome, Some(0.0));
+}
\ No newline at end of file
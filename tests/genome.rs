@@ -0,0 +1,99 @@
+mod common;
+
+use std::collections::BTreeSet;
+use bevy_neat::{FeedForwardGenome, GenomeBuilder, traits::{ConnGene, Genome, NodeGene}};
+use rand::Rng;
+
+#[test]
+fn add_conn_mut_stays_acyclic_until_it_runs_out() {
+    let config = common::connected_config(3, 2);
+    let mut genome = FeedForwardGenome::minimal(&config);
+
+    for _ in 0..6 {
+        genome.add_node_mut(&config);
+    }
+
+    let mut added = 0;
+
+    while genome.add_conn_mut(&config).is_some() {
+        added += 1;
+        assert!(added < 1000, "add_conn_mut never ran out of connections");
+    }
+
+    // Six hidden nodes and the inputs and outputs admit every forward pair, so the mutation must have added some.
+    assert!(added > 0);
+    assert!(genome.add_conn_mut(&config).is_none());
+
+    genome.compile(&config);
+    assert_eq!(genome.validate(&config), Ok(()));
+}
+
+/// Returns the ids reachable from `start` by following `(from, to)` edges.
+fn reachable(start: impl Iterator<Item = u32>, edges: &[(u32, u32)]) -> BTreeSet<u32> {
+    let mut reached = BTreeSet::new();
+    let mut stack = start.collect::<Vec<_>>();
+
+    while let Some(node) = stack.pop() {
+        if reached.insert(node) {
+            stack.extend(edges.iter().filter(|(from, _)| *from == node).map(|(_, to)| *to));
+        }
+    }
+
+    reached
+}
+
+#[test]
+fn deletions_leave_no_dead_end_or_unfed_hidden_nodes() {
+    let config = common::connected_config(3, 2);
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..50 {
+        let mut genome = FeedForwardGenome::minimal(&config);
+
+        for _ in 0..10 {
+            genome.add_node_mut(&config);
+            genome.add_conn_mut(&config);
+        }
+
+        for _ in 0..10 {
+            match rng.gen_bool(0.5) {
+                true => genome.del_conn_mut(&config).map(|_| ()),
+                false => genome.del_node_mut(&config).map(|_| ()),
+            };
+
+            let edges = genome.conn_genes().map(|conn| (conn.in_node().id(), conn.out_node().id())).collect::<Vec<_>>();
+            let reversed = edges.iter().map(|&(from, to)| (to, from)).collect::<Vec<_>>();
+
+            let fed = reachable(genome.input_nodes().map(|node| node.id()), &edges);
+            let useful = reachable(genome.output_nodes().map(|node| node.id()), &reversed);
+
+            assert!(genome.hidden_nodes().all(|node| fed.contains(&node.id()) && useful.contains(&node.id())));
+            assert_eq!(genome.validate(&config), Ok(()));
+        }
+    }
+}
+
+#[test]
+fn deletions_keep_a_bias_node_they_do_not_strand() {
+    let config = common::config(2, 1);
+    let mut builder = GenomeBuilder::new(&config);
+    let (in0, in1, out) = (builder.input(0), builder.input(1), builder.output(0));
+    let (fed, other, bias) = (builder.hidden(), builder.hidden(), builder.hidden());
+
+    builder.conn(in0, fed, 1.0).conn(in1, other, 1.0).conn(bias, fed, 1.0).conn(bias, out, 1.0).conn(fed, out, 1.0).conn(other, out, 1.0);
+    let genome = builder.build().unwrap();
+
+    for _ in 0..50 {
+        let mut deleted = genome.clone();
+        deleted.del_conn_mut(&config);
+
+        assert!(deleted.hidden_nodes().any(|node| node.id() == bias));
+        assert_eq!(deleted.validate(&config), Ok(()));
+
+        let mut deleted = genome.clone();
+        let node = deleted.del_node_mut(&config).unwrap();
+
+        assert!(node.id() == bias || deleted.hidden_nodes().any(|node| node.id() == bias));
+        assert_eq!(deleted.validate(&config), Ok(()));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,72 @@
+mod common;
+
+use bevy_neat::{Config, FeedForwardGenome, GenomeBuilder, Sigmoid, Substrate};
+use bevy::math::Vec2;
+
+// The index of the CPPN input that receives the x coordinate of the source node of a 2D substrate.
+const SOURCE_X: usize = 0;
+// The index of the CPPN input that receives the bias of a 2D substrate.
+const BIAS: usize = 4;
+
+/// Builds a CPPN for a 2D substrate whose output only depends on a single input, through the given weight.
+fn cppn(config: &Config<FeedForwardGenome>, input: usize, weight: f32) -> FeedForwardGenome {
+    let mut builder = GenomeBuilder::new(config);
+    let (input, output) = (builder.input(input), builder.output(0));
+
+    builder.conn(input, output, weight);
+    builder.build().unwrap()
+}
+
+fn substrate() -> Substrate {
+    Substrate::new_2d(
+        vec![Vec2::new(-5.0, 0.0), Vec2::new(5.0, 0.0)],
+        vec![vec![Vec2::new(-5.0, 1.0), Vec2::new(-4.0, 1.0), Vec2::new(5.0, 1.0)]],
+        vec![Vec2::new(0.0, 2.0)],
+    )
+}
+
+#[test]
+fn query_connects_consecutive_layers() {
+    let substrate = substrate();
+    let config = common::config(substrate.cppn_input_len(), 1);
+    let mut cppn = cppn(&config, BIAS, 10.0).compile(&config);
+
+    let network = substrate.query(&mut cppn, Sigmoid, 0.2, 3.0);
+
+    assert_eq!(substrate.cppn_input_len(), 5);
+    assert_eq!(network.input_len(), 2);
+    assert_eq!(network.output_len(), 1);
+    assert_eq!(network.node_count(), substrate.node_count());
+    assert_eq!(network.edge_count(), 2 * 3 + 3);
+}
+
+#[test]
+fn query_leaves_out_weak_connections() {
+    let substrate = substrate();
+    let config = common::config(substrate.cppn_input_len(), 1);
+
+    // Without connections, the CPPN always outputs 0.5, which maps to a weight of zero.
+    let mut silent = GenomeBuilder::new(&config).build().unwrap().compile(&config);
+    assert_eq!(substrate.query(&mut silent, Sigmoid, 0.2, 3.0).edge_count(), 0);
+
+    // Sources on the left barely move the output away from 0.5, while sources on the right saturate it, so only the
+    // connections out of the right input node and the right hidden node are kept.
+    let mut by_source = cppn(&config, SOURCE_X, 10.0).compile(&config);
+    assert_eq!(substrate.query(&mut by_source, Sigmoid, 0.2, 3.0).edge_count(), 3 + 1);
+}
+
+#[test]
+fn low_cppn_outputs_give_negative_weights() {
+    let substrate = Substrate::new_2d(vec![Vec2::ZERO], Vec::new(), vec![Vec2::Y]);
+    let config = common::config(substrate.cppn_input_len(), 1);
+    let mut cppn = cppn(&config, BIAS, -10.0).compile(&config);
+
+    let mut network = substrate.query(&mut cppn, Sigmoid, 0.2, 3.0);
+    assert_eq!(network.edge_count(), 1);
+
+    // The input passes through the sigmoid as well, so it always arrives positive and a negative weight pulls the
+    // output below the sigmoid of zero.
+    for x in [-2.0, 0.0, 2.0] {
+        assert!(network.activate(&[x])[0] < 0.5);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,53 @@
+mod common;
+
+use bevy_neat::{GenomeBuilder, Layout};
+
+#[test]
+fn layers_follow_the_longest_path_from_the_inputs() {
+    let config = common::config(2, 2);
+    let mut builder = GenomeBuilder::new(&config);
+    let (x, y, out_a, out_b) = (builder.input(0), builder.input(1), builder.output(0), builder.output(1));
+    let (a, b, c) = (builder.hidden(), builder.hidden(), builder.hidden());
+
+    builder.conn(x, a, 1.0).conn(a, b, 1.0).conn(y, c, 1.0).conn(c, b, 1.0).conn(b, out_a, 1.0).conn(y, out_b, 1.0);
+    let layout = Layout::new(&builder.build().unwrap());
+
+    assert_eq!(layout.layers(), [vec![x, y], vec![a, c], vec![b], vec![out_a, out_b]]);
+
+    for (node, position) in layout.positions() {
+        assert!((0.0..=1.0).contains(&position.x) && (0.0..=1.0).contains(&position.y), "node {node} at {position}");
+    }
+
+    assert_eq!(layout.position(x).unwrap().x, 0.0);
+    assert_eq!(layout.position(out_b).unwrap().x, 1.0);
+}
+
+#[test]
+fn barycenter_ordering_removes_avoidable_crossings() {
+    let config = common::config(2, 2);
+    let mut builder = GenomeBuilder::new(&config);
+    let (x, y, out_a, out_b) = (builder.input(0), builder.input(1), builder.output(0), builder.output(1));
+    let (a, b) = (builder.hidden(), builder.hidden());
+
+    // In id order, `a` sits above `b` and both pairs of connections cross.
+    builder.conn(x, b, 1.0).conn(y, a, 1.0).conn(b, out_a, 1.0).conn(a, out_b, 1.0);
+    let layout = Layout::new(&builder.build().unwrap());
+
+    assert_eq!(layout.layers()[1], [b, a]);
+    assert_eq!(layout.crossings(), 0);
+}
+
+#[test]
+fn crossings_count_connections_that_skip_layers() {
+    let config = common::config(2, 3);
+    let mut builder = GenomeBuilder::new(&config);
+    let (x, y, out_a, out_c) = (builder.input(0), builder.input(1), builder.output(0), builder.output(2));
+    let hidden = builder.hidden();
+
+    // The connection from `x` to the last output skips the hidden layer and crosses the one from `y` on the way.
+    builder.conn(x, out_c, 1.0).conn(y, hidden, 1.0).conn(hidden, out_a, 1.0);
+    let layout = Layout::new(&builder.build().unwrap());
+
+    assert_eq!(layout.layers().len(), 3);
+    assert_eq!(layout.crossings(), 1);
+}
\ No newline at end of file
@@ -0,0 +1,52 @@
+mod common;
+
+use bevy_neat::{Degrees, FeedForwardGenome, GenomeBuilder, Metrics, traits::Genome};
+
+#[test]
+fn metrics_of_a_known_genome() {
+    let config = common::config(3, 2);
+    let mut builder = GenomeBuilder::new(&config);
+
+    let (a, b, c) = (builder.input(0), builder.input(1), builder.input(2));
+    let (x, y) = (builder.output(0), builder.output(1));
+    let (first, second) = (builder.hidden(), builder.hidden());
+
+    builder
+        .conn(a, first, 1.0)
+        .conn(b, first, 1.0)
+        .conn(first, second, 1.0)
+        .conn(second, x, 1.0)
+        .conn(a, x, 1.0)
+        .disabled_conn(c, y, 1.0);
+
+    assert_eq!(Metrics::new(&builder.build().unwrap()), Metrics {
+        input_nodes: 3,
+        hidden_nodes: 2,
+        output_nodes: 2,
+        enabled_conns: 5,
+        disabled_conns: 1,
+        depth: 3,
+        fan_in: Degrees { min: 0, max: 2, mean: 1.25 },
+        fan_out: Degrees { min: 0, max: 2, mean: 1.0 },
+        recurrent_conns: 0,
+        connected_inputs: 2.0 / 3.0,
+    });
+}
+
+#[test]
+fn metrics_of_an_unconnected_genome() {
+    let config = common::config(2, 1);
+
+    assert_eq!(Metrics::new(&FeedForwardGenome::minimal(&config)), Metrics {
+        input_nodes: 2,
+        hidden_nodes: 0,
+        output_nodes: 1,
+        enabled_conns: 0,
+        disabled_conns: 0,
+        depth: 0,
+        fan_in: Degrees { min: 0, max: 0, mean: 0.0 },
+        fan_out: Degrees { min: 0, max: 0, mean: 0.0 },
+        recurrent_conns: 0,
+        connected_inputs: 0.0,
+    });
+}
\ No newline at end of file
@@ -0,0 +1,33 @@
+mod common;
+
+use bevy_neat::{FeedForwardGenome, traits::Genome};
+use rand::Rng;
+
+#[test]
+fn batched_activation_matches_per_sample_activation() {
+    let config = common::connected_config(4, 3);
+
+    let mut rng = rand::thread_rng();
+    let mut genome = FeedForwardGenome::minimal(&config);
+
+    for _ in 0..20 {
+        genome.add_node_mut(&config);
+        genome.add_conn_mut(&config);
+        genome.mut_conn_weight(&config);
+    }
+
+    let mut network = genome.compile(&config);
+    let samples = 37;
+    let inputs = (0..samples * network.input_len()).map(|_| rng.gen_range(-2.0..2.0)).collect::<Vec<f32>>();
+
+    // A larger batch first, so that the scratch buffer is reused rather than freshly sized for the checked batch.
+    network.activate_batch(&[inputs.clone(), inputs.clone()].concat());
+
+    let batched = network.activate_batch(&inputs);
+    let mut output = vec![0.0; network.output_len()];
+
+    for (row, expected) in inputs.chunks_exact(network.input_len()).zip(batched.chunks_exact(network.output_len())) {
+        network.activate_into(row, &mut output);
+        assert_eq!(output, expected);
+    }
+}
\ No newline at end of file
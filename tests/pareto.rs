@@ -0,0 +1,26 @@
+use bevy_neat::{crowding_distances, dominates, pareto_fronts};
+
+#[test]
+fn domination_needs_one_strictly_better_objective() {
+    assert!(dominates(&[2.0, 2.0], &[1.0, 2.0]));
+    assert!(!dominates(&[2.0, 2.0], &[2.0, 2.0]));
+    assert!(!dominates(&[3.0, 1.0], &[1.0, 3.0]));
+}
+
+#[test]
+fn fronts_are_sorted_best_first() {
+    let objectives = vec![vec![1.0, 5.0], vec![2.0, 4.0], vec![3.0, 3.0], vec![1.0, 1.0], vec![2.0, 2.0], vec![0.0, 0.0]];
+
+    assert_eq!(pareto_fronts(&objectives), vec![vec![0, 1, 2], vec![4], vec![3], vec![5]]);
+}
+
+#[test]
+fn crowding_distance_sums_normalized_neighbor_gaps() {
+    let objectives = vec![vec![0.0, 4.0], vec![1.0, 3.0], vec![2.0, 2.5], vec![4.0, 0.0]];
+    let fronts = pareto_fronts(&objectives);
+
+    assert_eq!(fronts, vec![vec![0, 1, 2, 3]]);
+
+    // The ends are always kept, and the middle members sum the gap between their neighbors over each range of 4.
+    assert_eq!(crowding_distances(&objectives, &fronts[0]), vec![f32::INFINITY, 0.5 + 0.375, 0.75 + 0.75, f32::INFINITY]);
+}
\ No newline at end of file
@@ -0,0 +1,162 @@
+mod common;
+
+use std::collections::BTreeSet;
+use bevy_neat::{Config, FeedForwardGenome, GenomeBuilder, InnovConflict, Population, SeedError, traits::{Config as _, ConnGene, Genome, NodeGene}};
+
+/// Builds a genome with a single connection from the given input to the output.
+fn single_conn(config: &Config<FeedForwardGenome>, input: usize) -> FeedForwardGenome {
+    let mut builder = GenomeBuilder::new(config);
+    let (input, output) = (builder.input(input), builder.output(0));
+
+    builder.conn(input, output, 1.0);
+    builder.build().unwrap()
+}
+
+#[test]
+fn seeded_copies_keep_every_weight_close() {
+    let seed = single_conn(&common::config(2, 1), 0);
+    let population = Population::from_genomes(common::config(2, 1), vec![seed.clone()]).unwrap();
+
+    assert_eq!(population.members().count(), 20);
+
+    for member in population.members() {
+        let (conn, seed_conn) = (member.conn_genes().next().unwrap(), seed.conn_genes().next().unwrap());
+
+        assert_eq!(conn.innov(), seed_conn.innov());
+        assert!((conn.weight() - seed_conn.weight()).abs() <= 0.1 + f32::EPSILON);
+    }
+}
+
+#[test]
+fn seeds_with_conflicting_innovations_are_rejected() {
+    let seed = single_conn(&common::config(2, 1), 0);
+
+    // The same innovation number goes to the connection from the other input in this config's history.
+    let config = common::config(2, 1);
+    let other = single_conn(&config, 1);
+    let (conn, other_conn) = (seed.conn_genes().next().unwrap(), other.conn_genes().next().unwrap());
+    assert_eq!(conn.innov(), other_conn.innov());
+
+    let conflicts = vec![InnovConflict { in_node: conn.in_node().id(), out_node: conn.out_node().id(), innov: conn.innov() }];
+    assert_eq!(Population::from_genomes(config, vec![seed.clone()]).err(), Some(SeedError::InnovConflicts(conflicts)));
+}
+
+#[test]
+fn seeds_conflicting_with_each_other_register_nothing() {
+    let (seed, other) = (single_conn(&common::config(2, 1), 0), single_conn(&common::config(2, 1), 1));
+    let conn = other.conn_genes().next().unwrap();
+    let config = common::config(2, 1);
+
+    let conflicts = vec![InnovConflict { in_node: conn.in_node().id(), out_node: conn.out_node().id(), innov: conn.innov() }];
+    assert_eq!(Population::from_genomes(config.clone(), vec![seed, other.clone()]).err(), Some(SeedError::InnovConflicts(conflicts)));
+
+    // The first seed was not registered, so the second one alone no longer conflicts.
+    assert!(Population::from_genomes(config, vec![other]).is_ok());
+}
+
+#[test]
+fn seeds_that_do_not_fit_the_config_are_rejected() {
+    let config = common::config(2, 1);
+    let seed = single_conn(&config, 0);
+
+    assert_eq!(Population::<FeedForwardGenome>::from_genomes(config.clone(), Vec::new()).err(), Some(SeedError::NoSeeds));
+    assert_eq!(
+        Population::from_genomes(config.clone(), vec![seed.clone(); 21]).err(),
+        Some(SeedError::TooManySeeds { seeds: 21, pop_size: 20 }),
+    );
+    assert_eq!(
+        Population::from_genomes(config, vec![seed, single_conn(&common::config(3, 1), 0)]).err(),
+        Some(SeedError::WrongShape { seed: 1, inputs: 3, outputs: 1 }),
+    );
+}
+
+#[test]
+fn ancestry_keeps_only_members_and_their_ancestors() {
+    let mut population = Population::<FeedForwardGenome>::new(common::config(2, 1));
+
+    for _ in 0..10 {
+        let last_id = population.members().map(|genome| genome.lineage().id).max().unwrap();
+        population.run(|genome, _| genome.conn_genes().map(|conn| conn.weight()).sum::<f32>().abs());
+
+        // Every offspring takes exactly one genome id, whether it was cloned or crossed over.
+        let mut bred = population.members()
+            .filter(|genome| genome.lineage().generation == population.generation())
+            .map(|genome| genome.lineage().id)
+            .collect::<Vec<_>>();
+
+        bred.sort();
+        assert_eq!(bred, (last_id + 1..=last_id + bred.len() as u32).collect::<Vec<_>>());
+    }
+
+    let mut kept = population.members().map(|genome| population.ancestry().ancestors(genome.lineage().id)).fold(
+        BTreeSet::new(),
+        |mut kept, ancestors| { kept.extend(ancestors.iter().map(|lineage| lineage.id)); kept },
+    );
+
+    assert!(population.members().all(|genome| kept.contains(&genome.lineage().id)));
+    assert!(population.ancestry().iter().all(|lineage| kept.remove(&lineage.id)));
+    assert!(kept.is_empty());
+}
+
+#[test]
+fn next_generation_keeps_the_size_and_assigns_every_member_a_species() {
+    let config = common::connected_config(2, 1);
+    let mut population = Population::<FeedForwardGenome>::new(config.clone());
+
+    for generation in 1..=5 {
+        population.run(|genome, _| genome.conn_genes().map(|conn| conn.weight()).sum::<f32>().abs());
+
+        assert_eq!(population.generation(), generation);
+        assert_eq!(population.members().count(), config.pop_size());
+        assert_eq!(population.species().iter().map(|species| species.members().len()).sum::<usize>(), config.pop_size());
+
+        // Classic speciation puts each genome with a representative within the compatibility threshold.
+        for species in population.species() {
+            assert!(!species.members().is_empty());
+            assert!(species.members().iter().all(|genome| genome.comp_dist(species.representative(), &config) < population.comp_thresh()));
+        }
+
+        for genome in population.members() {
+            assert_eq!(genome.validate(&config), Ok(()));
+        }
+    }
+}
+
+#[test]
+fn elites_are_copied_and_only_survivors_breed() {
+    let config = common::connected_config(2, 1).with_elitism(1).with_survival_threshold(0.2);
+    let mut population = Population::<FeedForwardGenome>::new(config.clone());
+
+    let fitness = |genome: &FeedForwardGenome| genome.conn_genes().map(|conn| conn.weight()).sum::<f32>();
+
+    // A fresh population is a single species, so its four fittest members are the only survivors.
+    let mut ranked = population.members().cloned().collect::<Vec<_>>();
+    ranked.sort_by(|a, b| fitness(b).total_cmp(&fitness(a)));
+
+    let survivors = ranked[..4].iter().map(|genome| genome.lineage().id).collect::<BTreeSet<_>>();
+    population.run(|genome, _| fitness(genome));
+
+    let elite = population.members().find(|genome| genome.lineage().id == ranked[0].lineage().id).unwrap();
+    assert_eq!(elite, &ranked[0]);
+    assert_eq!(elite.fitness(), Some(fitness(&ranked[0])));
+
+    for genome in population.members().filter(|genome| genome.lineage().id != ranked[0].lineage().id) {
+        assert!(genome.lineage().parents.iter().all(|parent| survivors.contains(parent)), "{:?}", genome.lineage());
+    }
+}
+
+#[test]
+fn complexity_limits_hold_over_generations() {
+    let config = common::connected_config(3, 1).with_complexity_limits(2, 7);
+    let mut population = Population::<FeedForwardGenome>::new(config);
+
+    // Rewarding size pushes the population against both limits.
+    for _ in 0..30 {
+        population.run(|genome, _| (genome.hidden_nodes().count() + genome.conn_genes().count()) as f32);
+
+        for genome in population.members() {
+            assert!(genome.hidden_nodes().count() <= 2);
+            assert!(genome.conn_genes().count() <= 7);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,86 @@
+mod common;
+
+use std::collections::BTreeSet;
+use bevy_neat::{Config, FeedForwardGenome, GenomeBuilder, traits::{Config as _, ConnGene, Genome, NodeGene}};
+use rand::Rng;
+
+fn assert_same_outputs(lhs: &FeedForwardGenome, rhs: &FeedForwardGenome, config: &Config<FeedForwardGenome>) {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..50 {
+        let input = (0..3).map(|_| rng.gen_range(-3.0..3.0)).collect::<Vec<f32>>();
+
+        for (lhs, rhs) in lhs.activate(input.clone(), config).into_iter().zip(rhs.activate(input, config)) {
+            assert!((lhs - rhs).abs() < 1e-6, "{lhs} != {rhs}");
+        }
+    }
+}
+
+#[test]
+fn pruning_keeps_outputs_and_drops_useless_structure() {
+    let config = common::connected_config(3, 2);
+    let mut builder = GenomeBuilder::new(&config);
+
+    let (a, b, c) = (builder.input(0), builder.input(1), builder.input(2));
+    let (x, y) = (builder.output(0), builder.output(1));
+    let (live, dead_end, source, constant, isolated) = (builder.hidden(), builder.hidden(), builder.hidden(), builder.hidden(), builder.hidden());
+
+    builder
+        .conn(a, live, 1.5)
+        .conn(b, live, -0.7)
+        .conn(live, x, 2.0)
+        .disabled_conn(c, x, 3.0)
+        .conn(a, dead_end, 1.0)
+        .conn(source, constant, 0.8)
+        .conn(constant, live, -1.2)
+        .conn(constant, y, 0.6)
+        .conn(c, y, 0.4);
+
+    let genome = builder.build().unwrap();
+    let pruned = genome.pruned(&config);
+
+    assert_same_outputs(&genome, &pruned, &config);
+    assert_eq!(pruned.validate(&config), Ok(()));
+    assert!(pruned.conn_genes().all(|conn| conn.enabled()));
+
+    // Only the live node and the source the constants are folded into are left.
+    let hidden = pruned.hidden_nodes().map(|node| node.id()).collect::<BTreeSet<_>>();
+    assert_eq!(hidden.len(), 2);
+    assert!(hidden.contains(&live) && hidden.contains(&source));
+    assert!(![dead_end, constant, isolated].iter().any(|node| hidden.contains(node)));
+
+    // Pruning again changes neither the genome nor the innovation history.
+    let (x, y) = (pruned.output_nodes().next().unwrap().clone(), pruned.output_nodes().nth(1).unwrap().clone());
+    let (node_id, innov) = (config.node_id(), config.innov(x.clone(), y.clone()));
+
+    assert_eq!(pruned.pruned(&config), pruned);
+    assert_eq!(config.node_id(), node_id + 1);
+    assert_eq!(config.innov(y, x), innov + 1);
+}
+
+#[test]
+fn pruning_keeps_outputs_of_evolved_genomes() {
+    let config = common::connected_config(3, 2);
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..100 {
+        let mut genome = FeedForwardGenome::minimal(&config);
+
+        for _ in 0..rng.gen_range(0..40) {
+            match rng.gen_range(0..6) {
+                0 => genome.add_conn_mut(&config).map(|_| ()),
+                1 => genome.add_node_mut(&config).map(|_| ()),
+                2 => genome.del_conn_mut(&config).map(|_| ()),
+                3 => genome.del_node_mut(&config).map(|_| ()),
+                4 => genome.toggle_conn_mut(&config).map(|_| ()),
+                _ => genome.mut_conn_weight(&config).map(|_| ()),
+            };
+        }
+
+        let pruned = genome.pruned(&config);
+
+        assert_same_outputs(&genome, &pruned, &config);
+        assert_eq!(pruned.validate(&config), Ok(()));
+        assert_eq!(pruned.pruned(&config), pruned);
+    }
+}
\ No newline at end of file
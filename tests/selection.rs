@@ -0,0 +1,85 @@
+mod common;
+
+use std::{num::NonZeroUsize, ptr};
+use bevy_neat::{FeedForwardGenome, FitnessProportionate, RankBased, Tournament, Truncation, traits::{Genome, Selection}};
+use rand::{SeedableRng, rngs::StdRng};
+
+const DRAWS: usize = 10_000;
+const SEED: u64 = 0x5e1ec7;
+
+/// Ten parents ranked from most to least fit, with fitnesses from 10 down to 1.
+fn parents() -> Vec<FeedForwardGenome> {
+    let config = common::config(2, 1);
+
+    (0..10).map(|rank| {
+        let mut genome = FeedForwardGenome::minimal(&config);
+        genome.set_fitness(10.0 - rank as f32, &config);
+        genome
+    }).collect()
+}
+
+/// Counts how often each rank is picked over many seeded draws.
+fn picks(selection: &impl Selection<FeedForwardGenome>) -> Vec<usize> {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let parents = parents();
+    let ranked = parents.iter().collect::<Vec<_>>();
+    let mut picks = vec![0; ranked.len()];
+
+    for _ in 0..DRAWS {
+        let picked = selection.select(&ranked, &mut rng);
+        picks[ranked.iter().position(|&parent| ptr::eq(parent, picked)).unwrap()] += 1;
+    }
+
+    picks
+}
+
+/// The mean picked rank, which is 4.5 when every rank is equally likely.
+fn mean_rank(picks: &[usize]) -> f32 {
+    picks.iter().enumerate().map(|(rank, &count)| (rank * count) as f32).sum::<f32>() / DRAWS as f32
+}
+
+// The expected mean ranks below lie around 2 to 2.7, so the bounds hold for any seed rather than just this one.
+
+#[test]
+fn tournament_favors_fitter_parents() {
+    let picks = picks(&Tournament::new(NonZeroUsize::new(3).unwrap()));
+
+    assert!(mean_rank(&picks) < 3.5, "{picks:?}");
+    assert!(picks[0] > picks[9], "{picks:?}");
+}
+
+#[test]
+fn fitness_proportionate_favors_fitter_parents_and_skips_the_least_fit() {
+    let picks = picks(&FitnessProportionate);
+
+    assert!(mean_rank(&picks) < 3.5, "{picks:?}");
+    assert_eq!(picks[9], 0);
+}
+
+#[test]
+fn rank_based_favors_fitter_parents() {
+    let picks = picks(&RankBased::new(2.0));
+
+    assert!(mean_rank(&picks) < 3.5, "{picks:?}");
+    assert_eq!(picks[9], 0);
+}
+
+#[test]
+fn truncation_never_picks_below_its_cutoff() {
+    let picks = picks(&Truncation::new(0.3));
+
+    assert!(picks[..3].iter().all(|&count| count > 0), "{picks:?}");
+    assert!(picks[3..].iter().all(|&count| count == 0), "{picks:?}");
+}
+
+#[test]
+#[should_panic(expected = "rank-based selection pressure must be in [1, 2]")]
+fn rank_based_rejects_pressure_out_of_range() {
+    RankBased::new(2.5);
+}
+
+#[test]
+#[should_panic(expected = "truncation fraction must be in (0, 1]")]
+fn truncation_rejects_empty_fraction() {
+    Truncation::new(0.0);
+}
\ No newline at end of file
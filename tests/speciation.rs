@@ -0,0 +1,50 @@
+mod common;
+
+use std::collections::BTreeSet;
+use bevy_neat::{FeedForwardGenome, GenomeBuilder, KMedoids, Population, traits::{Config as _, ConnGene, Genome, Speciation}};
+
+#[test]
+fn k_medoids_sorts_genomes_into_k_species() {
+    let config = common::config(2, 1);
+
+    let genomes = [0.0, 0.1, 0.2, 5.0, 5.1, 5.2, 10.0, 10.1, 10.2].map(|weight| {
+        let mut builder = GenomeBuilder::new(&config);
+        builder.conn(builder.input(0), builder.output(0), weight);
+        builder.build().unwrap()
+    });
+
+    let speciation = KMedoids { count: 3, iterations: 10 };
+    let assignments = speciation.speciate(&genomes, &[], config.comp_thresh(), &config);
+    assert_eq!(assignments.iter().collect::<BTreeSet<_>>().len(), 3);
+
+    // With existing species, the clusters claim both of them and found a single new one.
+    let representatives = [&genomes[0], &genomes[8]];
+    let species = speciation.speciate(&genomes, &representatives, config.comp_thresh(), &config).into_iter().collect::<BTreeSet<_>>();
+
+    assert_eq!(species.len(), 3);
+    assert!(species.contains(&0) && species.contains(&1));
+}
+
+#[test]
+fn k_medoids_never_founds_empty_species_for_identical_genomes() {
+    let config = common::config(2, 1);
+    let mut builder = GenomeBuilder::new(&config);
+    builder.conn(builder.input(0), builder.output(0), 1.0);
+
+    let genome = builder.build().unwrap();
+    let genomes = [genome.clone(), genome.clone(), genome.clone()];
+
+    let assignments = KMedoids { count: 3, iterations: 10 }.speciate(&genomes, &[], config.comp_thresh(), &config);
+    assert_eq!(assignments, vec![0, 0, 0]);
+}
+
+#[test]
+fn population_with_k_medoids_keeps_k_species() {
+    let config = common::connected_config(2, 1).with_speciation(KMedoids { count: 3, iterations: 10 });
+    let mut population = Population::<FeedForwardGenome>::new(config);
+
+    for _ in 0..5 {
+        population.run(|genome, _| genome.conn_genes().map(|conn| conn.weight()).sum::<f32>());
+        assert_eq!(population.species().len(), 3);
+    }
+}
\ No newline at end of file